@@ -0,0 +1,164 @@
+// Display arrangement
+// Reads display bounds from Core Graphics and moves displays to new origins in the global
+// display coordinate space, where the main display's top-left corner is at (0, 0).
+
+use core_graphics::display::{CGConfigureOption, CGDisplay};
+use core_graphics::geometry::CGRect;
+
+use crate::error::Error;
+use crate::layout::{Bounds, Layout, LayoutDisplay, LayoutIssue, Origin};
+use crate::mode_set::CgError;
+
+impl Bounds {
+    pub fn from_rect(rect: CGRect) -> Self {
+        Bounds {
+            origin: Origin {
+                x: rect.origin.x.round() as i32,
+                y: rect.origin.y.round() as i32,
+            },
+            width: rect.size.width.round() as i32,
+            height: rect.size.height.round() as i32,
+        }
+    }
+
+    /// Current bounds of an active display
+    pub fn of_display(display_id: u32) -> Self {
+        Bounds::from_rect(CGDisplay::new(display_id).bounds())
+    }
+}
+
+/// Move displays to new origins in one Core Graphics configuration transaction
+pub fn configure_origins(origins: &[(u32, Origin)]) -> Result<(), Error> {
+    let main = CGDisplay::main();
+    let config = main.begin_configuration().map_err(|err| {
+        Error::Platform(format!(
+            "Failed to begin display configuration: {}",
+            CgError(err)
+        ))
+    })?;
+
+    for (display_id, origin) in origins {
+        let result =
+            CGDisplay::new(*display_id).configure_display_origin(&config, origin.x, origin.y);
+        if let Err(err) = result {
+            let _ = main.cancel_configuration(&config);
+            return Err(Error::Platform(format!(
+                "Failed to move display ID {} to ({}, {}): {}",
+                display_id,
+                origin.x,
+                origin.y,
+                CgError(err)
+            )));
+        }
+    }
+
+    main.complete_configuration(&config, CGConfigureOption::ConfigurePermanently)
+        .map_err(|err| {
+            Error::Platform(format!(
+                "Failed to complete display configuration: {}",
+                CgError(err)
+            ))
+        })
+}
+
+/// A display moved by `arrange_displays`
+pub struct Move {
+    pub display_id: u32,
+    pub from: Origin,
+    pub to: Origin,
+}
+
+/// Outcome of `arrange_displays`
+pub struct Arrangement {
+    /// Problems with the requested origins that snapping fixed
+    pub issues: Vec<LayoutIssue>,
+    pub moves: Vec<Move>,
+}
+
+/// Current layout of the active displays with some origins replaced
+///
+/// Returns the layout and each display's current origin. With `main`, that display is
+/// treated as the main display instead of the current one.
+fn current_layout(
+    requested: &[(u32, Origin)],
+    main: Option<u32>,
+) -> Result<(Layout, Vec<(u32, Origin)>), Error> {
+    let active_displays = CGDisplay::active_displays()
+        .map_err(|err| Error::Platform(format!("Failed to get displays (error code: {})", err)))?;
+
+    let mut current = Vec::with_capacity(active_displays.len());
+    let mut displays = Vec::with_capacity(active_displays.len());
+    for display_id in active_displays {
+        let display = CGDisplay::new(display_id);
+        // Mirrors share the bounds of the display they mirror and move with it
+        if display.mirrors_display() != 0 {
+            continue;
+        }
+        let bounds = Bounds::of_display(display_id);
+        let origin = requested
+            .iter()
+            .find(|(id, _)| *id == display_id)
+            .map_or(bounds.origin, |(_, origin)| *origin);
+        current.push((display_id, bounds.origin));
+        displays.push(LayoutDisplay {
+            id: display_id,
+            bounds: Bounds { origin, ..bounds },
+            is_main: main.map_or(display.is_main(), |main| main == display_id),
+        });
+    }
+
+    if let Some(main) = main
+        && !current.iter().any(|(id, _)| *id == main)
+    {
+        return Err(Error::NotFound(format!(
+            "Display ID {} is not an active display or is a mirror",
+            main
+        )));
+    }
+
+    Ok((Layout::new(displays)?, current))
+}
+
+/// Move displays whose origin differs in `layout` in one configuration transaction
+fn move_displays(layout: &Layout, current: Vec<(u32, Origin)>) -> Result<Vec<Move>, Error> {
+    let moves: Vec<Move> = current
+        .into_iter()
+        .filter_map(|(display_id, from)| {
+            let to = layout.origin_of(display_id)?;
+            (to != from).then_some(Move {
+                display_id,
+                from,
+                to,
+            })
+        })
+        .collect();
+
+    if !moves.is_empty() {
+        let origins: Vec<(u32, Origin)> = moves.iter().map(|m| (m.display_id, m.to)).collect();
+        configure_origins(&origins)?;
+    }
+    Ok(moves)
+}
+
+/// Move displays towards requested origins, keeping the arrangement valid
+///
+/// The requested origins are combined with the current bounds of all active displays and
+/// snapped so displays share edges without overlapping, with the main display at (0, 0).
+/// Displays whose origin changes are moved in one configuration transaction.
+pub fn arrange_displays(requested: &[(u32, Origin)]) -> Result<Arrangement, Error> {
+    let (mut layout, current) = current_layout(requested, None)?;
+    let issues = layout.issues();
+    layout.solve()?;
+    let moves = move_displays(&layout, current)?;
+    Ok(Arrangement { issues, moves })
+}
+
+/// Make a display the main display, which carries the menu bar
+///
+/// macOS treats the display at (0, 0) as main, so the display moves to the origin and
+/// every other display shifts by the same amount, keeping the arrangement's shape.
+pub fn set_main_display(display_id: u32) -> Result<Vec<Move>, Error> {
+    let (mut layout, current) = current_layout(&[], Some(display_id))?;
+    layout.normalize();
+    move_displays(&layout, current)
+}
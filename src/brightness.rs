@@ -0,0 +1,18 @@
+// Display brightness
+// CoreDisplay's user brightness, the value behind the brightness slider in System Settings,
+// as a fraction from 0.0 to 1.0. Displays without adjustable brightness (most external
+// displays) report a value outside that range.
+
+// Link to CoreDisplay framework for brightness functions
+#[link(name = "CoreDisplay", kind = "framework")]
+unsafe extern "C" {
+    pub fn CoreDisplay_Display_SetUserBrightness(display: u32, brightness: f64);
+    pub fn CoreDisplay_Display_GetUserBrightness(display: u32) -> f64;
+}
+
+/// Current brightness as a fraction, or `None` if the display's brightness cannot be
+/// adjusted
+pub fn user_brightness(display_id: u32) -> Option<f64> {
+    let brightness = unsafe { CoreDisplay_Display_GetUserBrightness(display_id) };
+    (0.0..=1.0).contains(&brightness).then_some(brightness)
+}
@@ -0,0 +1,123 @@
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::desired_state::{Change, DesiredState, execute};
+use crate::mode_set::RetryPolicy;
+
+/// Exit code when the displays already match the desired state
+const EXIT_COMPLIANT: i32 = 0;
+/// Exit code when an error occurred
+const EXIT_ERROR: i32 = 1;
+/// Exit code when changes were made (or would be made with `--check`)
+const EXIT_CHANGED: i32 = 2;
+
+#[derive(Serialize)]
+struct Report<'a> {
+    status: &'a str,
+    applied: bool,
+    changes: &'a [Change],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+fn finish(report: Report, json: bool, exit_code: i32) -> ! {
+    if json {
+        match serde_json::to_string_pretty(&report) {
+            Ok(output) => println!("{}", output),
+            Err(err) => {
+                eprintln!("Error: Failed to serialize report: {}", err);
+                std::process::exit(EXIT_ERROR);
+            }
+        }
+    } else if let Some(err) = &report.error {
+        if report.applied {
+            println!(
+                "Applied {} change(s) before the error",
+                report.changes.len()
+            );
+        }
+        eprintln!("✗ {}", err);
+    } else if report.changes.is_empty() {
+        println!("✓ Displays already match the desired state");
+    } else if report.applied {
+        println!("✓ Applied {} change(s)", report.changes.len());
+    } else {
+        println!("{} change(s) needed (not applied)", report.changes.len());
+    }
+
+    std::process::exit(exit_code);
+}
+
+pub fn apply_desired_state(path: &Path, check: bool, json: bool, force: bool, retry: RetryPolicy) {
+    if !json {
+        println!("=== Applying Desired State ===\n");
+    }
+
+    let changes = match DesiredState::load(path).and_then(|state| state.plan(force)) {
+        Ok(changes) => changes,
+        Err(err) => {
+            let report = Report {
+                status: "error",
+                applied: false,
+                changes: &[],
+                error: Some(err.to_string()),
+            };
+            finish(report, json, EXIT_ERROR);
+        }
+    };
+
+    if !json {
+        for change in changes.iter() {
+            println!(
+                "{} ({}): {} {} -> {}",
+                change.display, change.selector, change.property, change.current, change.desired
+            );
+        }
+        if !changes.is_empty() {
+            println!();
+        }
+    }
+
+    if changes.is_empty() {
+        let report = Report {
+            status: "compliant",
+            applied: false,
+            changes: &changes,
+            error: None,
+        };
+        finish(report, json, EXIT_COMPLIANT);
+    }
+
+    if check {
+        let report = Report {
+            status: "changed",
+            applied: false,
+            changes: &changes,
+            error: None,
+        };
+        finish(report, json, EXIT_CHANGED);
+    }
+
+    match execute(&changes, retry) {
+        Ok(()) => {
+            let report = Report {
+                status: "changed",
+                applied: true,
+                changes: &changes,
+                error: None,
+            };
+            finish(report, json, EXIT_CHANGED);
+        }
+        Err((applied, err)) => {
+            // Only the changes made before the failure are reported
+            let report = Report {
+                status: "error",
+                applied: applied > 0,
+                changes: &changes[..applied],
+                error: Some(err.to_string()),
+            };
+            finish(report, json, EXIT_ERROR);
+        }
+    }
+}
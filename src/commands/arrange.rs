@@ -0,0 +1,85 @@
+use crate::arrangement::{Arrangement, arrange_displays};
+use crate::error::Error;
+use crate::layout::{Alignment, Bounds, Origin, Placement, place_relative};
+use crate::selector::{DisplaySelector, connected_displays};
+
+/// Where to put a display: an absolute origin or next to another display
+pub enum Position {
+    Absolute(Origin),
+    Relative {
+        placement: Placement,
+        anchor: String,
+        alignment: Option<Alignment>,
+    },
+}
+
+pub fn arrange(display: &str, position: &Position) {
+    println!("=== Arranging Display ===\n");
+
+    match arrange_display(display, position) {
+        Ok((display_id, requested, Arrangement { issues, moves })) => {
+            println!("Display ID: {}", display_id);
+            println!("Requested origin: {}", requested);
+            for issue in issues.iter() {
+                println!("Snapping: {}", issue);
+            }
+            if moves.is_empty() {
+                println!("\n✓ Displays already arranged");
+                return;
+            }
+            for m in moves.iter() {
+                println!("Display ID {}: {} -> {}", m.display_id, m.from, m.to);
+            }
+            if !moves
+                .iter()
+                .any(|m| m.display_id == display_id && m.to == requested)
+            {
+                println!(
+                    "Note: Displays were snapped together with the main display kept at (0, 0)"
+                );
+            }
+            println!("\n✓ Display arranged");
+        }
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn arrange_display(
+    display: &str,
+    position: &Position,
+) -> Result<(u32, Origin, Arrangement), Error> {
+    let displays = connected_displays()?;
+    let selector: DisplaySelector = display.parse()?;
+    let display_id = selector.select_one(&displays)?.id;
+    let bounds = Bounds::of_display(display_id);
+
+    let origin = match position {
+        Position::Absolute(origin) => *origin,
+        Position::Relative {
+            placement,
+            anchor,
+            alignment,
+        } => {
+            let anchor_selector: DisplaySelector = anchor.parse()?;
+            let anchor_id = anchor_selector.select_one(&displays)?.id;
+            if anchor_id == display_id {
+                return Err(Error::Invalid(
+                    "A display cannot be placed relative to itself".to_string(),
+                ));
+            }
+            place_relative(
+                bounds.width,
+                bounds.height,
+                &Bounds::of_display(anchor_id),
+                *placement,
+                *alignment,
+            )?
+        }
+    };
+
+    let arrangement = arrange_displays(&[(display_id, origin)])?;
+    Ok((display_id, origin, arrangement))
+}
@@ -0,0 +1,128 @@
+use std::path::Path;
+
+use crate::displayplacer::DisplayplacerConfig;
+use crate::error::Error;
+use crate::mode_descriptor::{ModeDescriptor, mode_snapshots};
+use crate::monitor_panel::MPDisplayMgr;
+use crate::profile::{DisplayState, Profile, capture_profile, load_profile, save_profile};
+use crate::selector::DisplaySelector;
+
+pub fn displayplacer_import(args: &[String], output: Option<&Path>, profile: Option<&str>) {
+    let config: DisplayplacerConfig = match args.join(" ").parse() {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            std::process::exit(1);
+        }
+    };
+
+    if config.displays.iter().any(|display| display.ids.len() > 1) {
+        eprintln!("Note: mirror sets (id:A+B) are imported as separate displays without mirroring");
+    }
+
+    if let Some(name) = profile {
+        match resolve_profile(&config, name).and_then(|profile| save_profile(&profile)) {
+            Ok(path) => println!("✓ Saved profile '{}' to {}", name, path.display()),
+            Err(err) => {
+                eprintln!("Error: {}", err);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    let contents = config.to_desired_state().and_then(|state| {
+        toml::to_string_pretty(&state)
+            .map_err(|err| Error::Format(format!("Failed to serialize desired state: {}", err)))
+    });
+    let contents = match contents {
+        Ok(contents) => contents,
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            std::process::exit(1);
+        }
+    };
+
+    match output {
+        Some(path) => {
+            if let Err(err) = std::fs::write(path, contents) {
+                eprintln!("Error: Failed to write {}: {}", path.display(), err);
+                std::process::exit(1);
+            }
+            println!("✓ Wrote desired state to {}", path.display());
+        }
+        None => print!("{}", contents),
+    }
+}
+
+/// Build a profile by resolving each entry against the connected displays' modes
+fn resolve_profile(config: &DisplayplacerConfig, name: &str) -> Result<Profile, Error> {
+    let desired = config.to_desired_state()?;
+    let mgr = unsafe { MPDisplayMgr::shared().or_else(|| MPDisplayMgr::new()) }
+        .ok_or_else(|| Error::Platform("MonitorPanel manager not available".to_string()))?;
+
+    let mut displays = Vec::new();
+    for entry in desired.displays.iter() {
+        let mp_display = match entry.selector.parse::<DisplaySelector>()? {
+            DisplaySelector::Uuid(uuid) => unsafe { mgr.display_with_uuid(&uuid) },
+            DisplaySelector::Id(id) => unsafe { mgr.display_with_id(id) },
+            _ => None,
+        }
+        .ok_or_else(|| Error::NotFound(format!("Display {} is not connected", entry.selector)))?;
+
+        let uuid = unsafe { mp_display.uuid() }.ok_or_else(|| {
+            Error::NotFound(format!(
+                "Display {} has no persistent screen id",
+                entry.selector
+            ))
+        })?;
+
+        let mode = match &entry.mode {
+            Some(request) => {
+                let modes = unsafe { mode_snapshots(&mp_display) };
+                let chosen = request.choose(&modes, None).ok_or_else(|| {
+                    Error::NotFound(format!(
+                        "Display {} has no mode matching {}",
+                        entry.selector, request
+                    ))
+                })?;
+                Some(chosen.descriptor.clone())
+            }
+            None => unsafe { mp_display.current_mode() }
+                .map(|mode| unsafe { ModeDescriptor::from_mode(&mp_display, &mode) }),
+        };
+
+        displays.push(DisplayState {
+            uuid,
+            name: unsafe { mp_display.display_name() },
+            output: None,
+            monitor: None,
+            brightness: None,
+            rotation: entry.rotation,
+            origin: entry.origin,
+            mode,
+            preset: None,
+            hdr: None,
+        });
+    }
+
+    Ok(Profile {
+        name: name.to_string(),
+        displays,
+    })
+}
+
+pub fn displayplacer_export(profile: Option<&str>) {
+    let result = match profile {
+        Some(name) => load_profile(name),
+        None => capture_profile("current"),
+    };
+
+    match result {
+        Ok(profile) => println!("{}", DisplayplacerConfig::from_profile(&profile)),
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            std::process::exit(1);
+        }
+    }
+}
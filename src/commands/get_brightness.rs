@@ -1,31 +1,33 @@
+use crate::brightness::user_brightness;
+use crate::display_name::connected_labels;
 use crate::monitor_panel::MPDisplayMgr;
+use crate::selector::select_displays;
 use core_graphics::display::CGDisplay;
 
-// Link to CoreDisplay framework for brightness functions
-#[link(name = "CoreDisplay", kind = "framework")]
-unsafe extern "C" {
-    fn CoreDisplay_Display_GetUserBrightness(display: u32) -> f64;
-}
-
-pub fn get_brightness(filter_display: Option<u32>) {
+pub fn get_brightness(filter_display: Option<&str>) {
     println!("=== Display Brightness Information ===\n");
 
     // Get list of active displays
     let displays = CGDisplay::active_displays().expect("Failed to get displays");
 
     // Filter displays if requested
-    let display_ids: Vec<_> = if let Some(id) = filter_display {
-        if displays.contains(&id) {
-            vec![id]
-        } else {
-            eprintln!("Error: Display ID {} not found", id);
-            std::process::exit(1);
+    let display_ids: Vec<_> = if let Some(selector) = filter_display {
+        match select_displays(selector) {
+            Ok(selected) => displays
+                .into_iter()
+                .filter(|display_id| selected.contains(display_id))
+                .collect(),
+            Err(err) => {
+                eprintln!("Error: {}", err);
+                std::process::exit(1);
+            }
         }
     } else {
         displays
     };
 
     println!("Found {} active display(s):\n", display_ids.len());
+    let labels = connected_labels();
 
     for (idx, display_id) in display_ids.iter().enumerate() {
         let display = CGDisplay::new(*display_id);
@@ -35,7 +37,7 @@ pub fn get_brightness(filter_display: Option<u32>) {
 
         // Get persistent screen ID from MonitorPanel
         unsafe {
-            if let Some(mgr) = MPDisplayMgr::new().or_else(|| MPDisplayMgr::shared()) {
+            if let Some(mgr) = MPDisplayMgr::shared().or_else(|| MPDisplayMgr::new()) {
                 if let Some(mp_displays) = mgr.displays() {
                     for mp_display in mp_displays.iter() {
                         if mp_display.display_id() == *display_id as i32 {
@@ -49,14 +51,15 @@ pub fn get_brightness(filter_display: Option<u32>) {
             }
         }
 
+        if let Some(label) = labels.iter().find(|label| label.id == *display_id) {
+            label.print("  ");
+        }
         println!("  Display Model: {}", display.model_number());
         println!("  Is built-in: {}", display.is_builtin());
 
         // Get current brightness using CoreDisplay
         // Note: This primarily works for built-in displays
-        let brightness = unsafe { CoreDisplay_Display_GetUserBrightness(*display_id) };
-
-        if brightness >= 0.0 && brightness <= 1.0 {
+        if let Some(brightness) = user_brightness(*display_id) {
             // Convert to percentage (brightness is returned as 0.0-1.0)
             let percentage = (brightness * 100.0).round() as u32;
             println!("  Brightness: {}%", percentage);
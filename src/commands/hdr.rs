@@ -0,0 +1,41 @@
+use crate::error::Error;
+use crate::selector::resolve_display;
+
+pub fn set_hdr(display: &str, enabled: bool) {
+    println!("=== Setting HDR ===\n");
+
+    match set_hdr_for(display, enabled) {
+        Ok(display_id) => {
+            println!("Display ID: {}", display_id);
+            println!("HDR: {}", if enabled { "on" } else { "off" });
+            println!("\n✓ HDR updated");
+        }
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn set_hdr_for(display: &str, enabled: bool) -> Result<u32, Error> {
+    let (display_id, mp_display) = resolve_display(display)?;
+
+    unsafe {
+        if !mp_display.has_hdr_modes() {
+            return Err(Error::Invalid(format!(
+                "Display ID {} has no HDR modes",
+                display_id
+            )));
+        }
+        mp_display.set_prefer_hdr_modes(enabled);
+
+        if mp_display.prefer_hdr_modes() != enabled {
+            return Err(Error::Platform(format!(
+                "Display ID {} did not turn HDR {}",
+                display_id,
+                if enabled { "on" } else { "off" }
+            )));
+        }
+    }
+    Ok(display_id)
+}
@@ -0,0 +1,72 @@
+use std::path::Path;
+
+use core_graphics::display::CGDisplay;
+
+use crate::diagram::{DiagramDisplay, render_ascii, render_svg};
+use crate::display_name::connected_labels;
+use crate::error::Error;
+use crate::layout::Bounds;
+
+/// Describe the active displays for a layout diagram
+///
+/// Indexes follow `list` output. Mirrors are left out since they share the bounds of the
+/// display they mirror.
+pub fn diagram_displays() -> Result<Vec<DiagramDisplay>, Error> {
+    let active_displays = CGDisplay::active_displays()
+        .map_err(|err| Error::Platform(format!("Failed to get displays (error code: {})", err)))?;
+    let labels = connected_labels();
+
+    let mut displays = Vec::with_capacity(active_displays.len());
+    for (idx, display_id) in active_displays.iter().enumerate() {
+        let display = CGDisplay::new(*display_id);
+        if display.mirrors_display() != 0 {
+            continue;
+        }
+
+        let name = labels
+            .iter()
+            .find(|label| label.id == *display_id)
+            .map_or_else(
+                || format!("Display {}", display_id),
+                |label| label.label.clone(),
+            );
+        let resolution = match display.display_mode() {
+            Some(mode) => format!("{}x{}", mode.width(), mode.height()),
+            None => format!("{}x{}", display.pixels_wide(), display.pixels_high()),
+        };
+
+        displays.push(DiagramDisplay {
+            index: idx + 1,
+            name,
+            resolution,
+            bounds: Bounds::of_display(*display_id),
+            is_main: display.is_main(),
+        });
+    }
+    Ok(displays)
+}
+
+/// Print the display arrangement as ASCII boxes, or write it to an SVG file
+pub fn show_layout(svg: Option<&Path>) {
+    let displays = match diagram_displays() {
+        Ok(displays) => displays,
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            std::process::exit(1);
+        }
+    };
+
+    match svg {
+        Some(path) => {
+            if let Err(err) = std::fs::write(path, render_svg(&displays)) {
+                eprintln!("Error: Failed to write {}: {}", path.display(), err);
+                std::process::exit(1);
+            }
+            println!("✓ Wrote display layout to {}", path.display());
+        }
+        None => {
+            println!("=== Display Layout ===\n");
+            print!("{}", render_ascii(&displays));
+        }
+    }
+}
@@ -1,26 +1,208 @@
 use core_graphics::display::CGDisplay;
+use serde::Serialize;
+
+use super::layout::diagram_displays;
+use crate::diagram::render_ascii;
+use crate::display_kind::{DisplayTag, display_kind, display_tags};
+use crate::display_name::{DisplayLabel, connected_labels};
+use crate::layout::Bounds;
+use crate::mode_descriptor::{ModeDescriptor, is_hdr_mode};
+use crate::mode_safety::running_in_safe_mode;
+use crate::monitor_panel::{MPDisplay, MPDisplayMgr};
+use crate::selector::select_displays;
+
+/// A display as printed by `list --json`
+#[derive(Serialize)]
+struct DisplayReport {
+    index: usize,
+    id: u32,
+    uuid: Option<String>,
+    /// Display name, with a numbered suffix when identical monitors are connected
+    name: Option<String>,
+    /// Three-letter PNP manufacturer id
+    vendor: Option<String>,
+    serial: Option<u32>,
+    model: u32,
+    is_main: bool,
+    has_menu_bar: bool,
+    is_builtin: bool,
+    /// Kind of device: `builtin`, `tv`, `projector`, `airplay`, `sidecar` or `monitor`
+    kind: String,
+    tags: Vec<DisplayTag>,
+    /// Rotation in degrees clockwise
+    rotation: Option<i32>,
+    can_rotate: bool,
+    has_rotation_sensor: bool,
+    /// IDs of the displays in the same mirror set, empty when not mirrored
+    mirror_set: Vec<u32>,
+    /// ID of the display whose picture this one shows, when mirrored
+    mirror_master: Option<u32>,
+    bounds: Bounds,
+    current_mode: Option<ModeDescriptor>,
+    /// Whether the display is in the mode macOS picks for it by default
+    in_default_mode: Option<bool>,
+    has_hdr_modes: bool,
+    /// Whether HDR is on, for displays with HDR modes
+    hdr: Option<bool>,
+}
 
-use crate::monitor_panel::MPDisplayMgr;
+fn display_report(
+    mgr: Option<&MPDisplayMgr>,
+    index: usize,
+    display_id: u32,
+    label: Option<&DisplayLabel>,
+) -> DisplayReport {
+    let display = CGDisplay::new(display_id);
+    let mp_display = mgr.and_then(|mgr| unsafe { mgr.display_with_id(display_id) });
+    let tags = mp_display
+        .as_ref()
+        .map(|d| unsafe { display_tags(d) })
+        .unwrap_or_default();
+    let (mirror_set, mirror_master) = match (mgr, &mp_display) {
+        (Some(mgr), Some(mp_display)) => unsafe { mirroring(mgr, mp_display) },
+        _ => (Vec::new(), None),
+    };
 
-pub fn list_displays(verbose: bool, filter_display: Option<u32>) {
-    println!("=== Display Information ===\n");
+    unsafe {
+        DisplayReport {
+            index,
+            id: display_id,
+            uuid: mp_display.as_ref().and_then(|d| d.uuid()),
+            name: label.map(|label| label.label.clone()),
+            vendor: label.map(|label| label.vendor.clone()),
+            serial: label.and_then(|label| label.serial),
+            model: display.model_number(),
+            is_main: display.is_main(),
+            has_menu_bar: mp_display
+                .as_ref()
+                .map_or(display.is_main(), |d| d.has_menu_bar()),
+            is_builtin: display.is_builtin(),
+            kind: display_kind(&tags),
+            tags,
+            rotation: mp_display.as_ref().map(|d| d.orientation()),
+            can_rotate: mp_display
+                .as_ref()
+                .is_some_and(|d| d.can_change_orientation()),
+            has_rotation_sensor: mp_display.as_ref().is_some_and(|d| d.has_rotation_sensor()),
+            mirror_set,
+            mirror_master,
+            bounds: mp_display.as_ref().map_or_else(
+                || Bounds::of_display(display_id),
+                |d| Bounds::from_rect(d.display_bounds()),
+            ),
+            current_mode: mp_display.as_ref().and_then(|d| {
+                d.current_mode()
+                    .map(|mode| ModeDescriptor::from_mode(d, &mode))
+            }),
+            in_default_mode: mp_display.as_ref().map(|d| d.in_default_mode()),
+            has_hdr_modes: mp_display.as_ref().is_some_and(|d| d.has_hdr_modes()),
+            hdr: mp_display
+                .as_ref()
+                .filter(|d| d.has_hdr_modes())
+                .map(|d| d.prefer_hdr_modes()),
+        }
+    }
+}
+
+/// Mirror set members and mirror master of a display, if it is mirrored
+unsafe fn mirroring(mgr: &MPDisplayMgr, mp_display: &MPDisplay) -> (Vec<u32>, Option<u32>) {
+    unsafe {
+        if !mp_display.is_mirrored() {
+            return (Vec::new(), None);
+        }
+        let mut members: Vec<u32> = mgr
+            .mirror_set_for_display(mp_display)
+            .unwrap_or_default()
+            .iter()
+            .map(|member| member.display_id() as u32)
+            .collect();
+        members.sort_unstable();
+        let master = if mp_display.is_mirror_master() {
+            mp_display.display_id()
+        } else {
+            mp_display.mirror_master_display_id()
+        };
+        (members, Some(master as u32))
+    }
+}
+
+/// Kind tags of a display, empty when MonitorPanel does not know it
+fn tags_of(mgr: Option<&MPDisplayMgr>, display_id: u32) -> Vec<DisplayTag> {
+    unsafe {
+        mgr.and_then(|mgr| mgr.display_with_id(display_id))
+            .map(|mp_display| display_tags(&mp_display))
+            .unwrap_or_default()
+    }
+}
 
+pub fn list_displays(
+    verbose: bool,
+    filter_display: Option<&str>,
+    filter_tag: Option<DisplayTag>,
+    layout: bool,
+    json: bool,
+) {
     // Get list of active displays
-    let displays = CGDisplay::active_displays().expect("Failed to get displays");
+    let mut displays = CGDisplay::active_displays().expect("Failed to get displays");
+    // One manager for every display listed
+    let mgr = unsafe { MPDisplayMgr::shared().or_else(|| MPDisplayMgr::new()) };
+    if let Some(tag) = filter_tag {
+        displays.retain(|display_id| tags_of(mgr.as_ref(), *display_id).contains(&tag));
+    }
 
     // Filter displays if requested
-    let display_ids: Vec<_> = if let Some(id) = filter_display {
-        if displays.contains(&id) {
-            vec![id]
-        } else {
-            eprintln!("Error: Display ID {} not found", id);
-            std::process::exit(1);
+    let display_ids: Vec<_> = if let Some(selector) = filter_display {
+        match select_displays(selector) {
+            Ok(selected) => displays
+                .into_iter()
+                .filter(|display_id| selected.contains(display_id))
+                .collect(),
+            Err(err) => {
+                eprintln!("Error: {}", err);
+                std::process::exit(1);
+            }
         }
     } else {
         displays
     };
 
+    let labels = connected_labels();
+    let label_of = |display_id: u32| labels.iter().find(|label| label.id == display_id);
+
+    if json {
+        let reports: Vec<DisplayReport> = display_ids
+            .iter()
+            .enumerate()
+            .map(|(idx, display_id)| {
+                display_report(mgr.as_ref(), idx + 1, *display_id, label_of(*display_id))
+            })
+            .collect();
+        match serde_json::to_string_pretty(&reports) {
+            Ok(output) => println!("{}", output),
+            Err(err) => {
+                eprintln!("Error: Failed to serialize displays: {}", err);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    println!("=== Display Information ===\n");
     println!("Found {} active display(s):\n", display_ids.len());
+    if unsafe { running_in_safe_mode() } {
+        println!("macOS is running in safe mode; mode changes are refused without --force\n");
+    }
+
+    if layout {
+        match diagram_displays() {
+            Ok(displays) => print!("{}", render_ascii(&displays)),
+            Err(err) => {
+                eprintln!("Error: {}", err);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
 
     for (idx, display_id) in display_ids.iter().enumerate() {
         let display = CGDisplay::new(*display_id);
@@ -29,14 +211,36 @@ pub fn list_displays(verbose: bool, filter_display: Option<u32>) {
         println!("  Contextual screen id: {}", display_id);
 
         // Get persistent screen ID from MonitorPanel
+        let mut display_bounds = None;
+        let mut hardware_bounds = None;
+        let mut has_menu_bar = None;
+        let mut rotation = None;
+        let mut tags = Vec::new();
+        let mut hdr = None;
+        let mut in_default_mode = None;
+        let mut mirror = (Vec::new(), None);
         unsafe {
-            if let Some(mgr) = MPDisplayMgr::new().or_else(|| MPDisplayMgr::shared()) {
+            if let Some(mgr) = &mgr {
                 if let Some(mp_displays) = mgr.displays() {
                     for mp_display in mp_displays.iter() {
                         if mp_display.display_id() == *display_id as i32 {
                             if let Some(uuid) = mp_display.uuid() {
                                 println!("  Persistent screen id: {}", uuid);
                             }
+                            display_bounds = Some(Bounds::from_rect(mp_display.display_bounds()));
+                            hardware_bounds = Some(Bounds::from_rect(mp_display.hardware_bounds()));
+                            has_menu_bar = Some(mp_display.has_menu_bar());
+                            rotation = Some((
+                                mp_display.orientation(),
+                                mp_display.can_change_orientation(),
+                                mp_display.has_rotation_sensor(),
+                            ));
+                            in_default_mode = Some(mp_display.in_default_mode());
+                            tags = display_tags(mp_display);
+                            hdr = mp_display
+                                .has_hdr_modes()
+                                .then(|| mp_display.prefer_hdr_modes());
+                            mirror = mirroring(mgr, mp_display);
                             break;
                         }
                     }
@@ -44,11 +248,61 @@ pub fn list_displays(verbose: bool, filter_display: Option<u32>) {
             }
         }
 
+        if let Some(label) = label_of(*display_id) {
+            label.print("  ");
+        }
         println!("  Display Model: {}", display.model_number());
         println!("  Width: {} pixels", display.pixels_wide());
         println!("  Height: {} pixels", display.pixels_high());
         println!("  Is main: {}", display.is_main());
+        if let Some(has_menu_bar) = has_menu_bar {
+            println!("  Has menu bar: {}", has_menu_bar);
+        }
         println!("  Is built-in: {}", display.is_builtin());
+        if tags.is_empty() {
+            println!("  Kind: {}", display_kind(&tags));
+        } else {
+            let names: Vec<String> = tags.iter().map(|tag| tag.name()).collect();
+            println!(
+                "  Kind: {} (tags: {})",
+                display_kind(&tags),
+                names.join(", ")
+            );
+        }
+        if let Some((degrees, can_rotate, has_sensor)) = rotation {
+            let note = match (can_rotate, has_sensor) {
+                (false, _) => " (fixed)",
+                (true, true) => " (rotation sensor)",
+                (true, false) => "",
+            };
+            println!("  Rotation: {}°{}", degrees, note);
+        }
+        if let Some(hdr) = hdr {
+            println!("  HDR: {}", if hdr { "on" } else { "off" });
+        }
+
+        if let (members, Some(master)) = &mirror {
+            let others: Vec<String> = members
+                .iter()
+                .filter(|id| *id != display_id)
+                .map(|id| id.to_string())
+                .collect();
+            if master == display_id {
+                println!(
+                    "  Mirroring: source for display ID(s) {}",
+                    others.join(", ")
+                );
+            } else {
+                println!("  Mirroring: shows display ID {}", master);
+            }
+        }
+
+        let bounds = display_bounds.unwrap_or_else(|| Bounds::of_display(*display_id));
+        println!("  Origin: {}", bounds.origin);
+        println!("  Bounds: {} points", bounds);
+        if let Some(hardware_bounds) = hardware_bounds {
+            println!("  Hardware bounds: {} pixels", hardware_bounds);
+        }
 
         // Get current display mode
         if let Some(mode) = display.display_mode() {
@@ -57,10 +311,14 @@ pub fn list_displays(verbose: bool, filter_display: Option<u32>) {
             println!("    Height: {}", mode.height());
             println!("    Refresh rate: {:.2} Hz", mode.refresh_rate());
         }
+        if let Some(in_default_mode) = in_default_mode {
+            println!("  In default mode: {}", in_default_mode);
+        }
 
         // List all available display modes using MonitorPanel framework
         if verbose {
-            list_display_modes(*display_id);
+            list_scan_settings(mgr.as_ref(), *display_id);
+            list_display_modes(mgr.as_ref(), *display_id);
         } else {
             println!("  Use --verbose to see all available display modes");
         }
@@ -69,16 +327,45 @@ pub fn list_displays(verbose: bool, filter_display: Option<u32>) {
     }
 }
 
-fn list_display_modes(display_id: u32) {
+fn list_scan_settings(mgr: Option<&MPDisplayMgr>, display_id: u32) {
+    unsafe {
+        let Some(mp_display) = mgr.and_then(|mgr| mgr.display_with_id(display_id)) else {
+            return;
+        };
+
+        if mp_display.supports_underscan() {
+            println!(
+                "  Underscan: {} (range {} to {})",
+                mp_display.underscan(),
+                mp_display.min_underscan(),
+                mp_display.max_underscan()
+            );
+        } else {
+            println!("  Underscan: not supported");
+        }
+
+        if mp_display.supports_overscan() {
+            println!(
+                "  Overscan: {}",
+                if mp_display.overscan_enabled() {
+                    "on"
+                } else {
+                    "off"
+                }
+            );
+        } else {
+            println!("  Overscan: not supported");
+        }
+    }
+}
+
+fn list_display_modes(mgr: Option<&MPDisplayMgr>, display_id: u32) {
     println!("  Available modes:");
     unsafe {
         // Get CGDisplay's current mode so we can mark the corresponding
         // MonitorPanel mode as current when listing.
         let cg_current_mode = CGDisplay::new(display_id).display_mode();
 
-        // Try both new() and shared() methods
-        let mgr = MPDisplayMgr::new().or_else(|| MPDisplayMgr::shared());
-
         if let Some(mgr) = mgr {
             // Try to get displays array
             if let Some(mp_displays) = mgr.displays() {
@@ -102,6 +389,8 @@ fn list_display_modes(display_id: u32) {
                             let mp_current_mode_num: Option<i32> = mp_display
                                 .current_mode()
                                 .and_then(|m| Some(m.mode_number()));
+                            // 10-bit modes only count as HDR on displays with HDR support
+                            let has_hdr_modes = mp_display.has_hdr_modes();
 
                             // Separate HiDPI and non-HiDPI modes with their mode numbers for sorting
                             let mut hidpi_modes: Vec<(i32, String)> = Vec::new();
@@ -120,6 +409,8 @@ fn list_display_modes(display_id: u32) {
                                 let is_native = mode.is_native_mode();
                                 let is_default = mode.is_default_mode();
                                 let is_visible = mode.is_user_visible();
+                                let is_hdr = is_hdr_mode(mode.bits_per_sample(), has_hdr_modes);
+                                let is_safe = mode.is_safe_mode();
 
                                 // Only show user-visible modes
                                 if is_visible {
@@ -153,6 +444,12 @@ fn list_display_modes(display_id: u32) {
                                             if is_default {
                                                 flags.push("Default");
                                             }
+                                            if is_hdr {
+                                                flags.push("HDR");
+                                            }
+                                            if is_safe {
+                                                flags.push("Safe");
+                                            }
                                             if !flags.is_empty() {
                                                 format!(" [{}]", flags.join(", "))
                                             } else {
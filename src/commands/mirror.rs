@@ -0,0 +1,243 @@
+use crate::error::Error;
+use crate::mirror_plan::{MirrorCandidate, plan_mirror};
+use crate::mode_descriptor::{format_refresh_rate, mode_snapshots};
+use crate::monitor_panel::{MPDisplay, MPDisplayMgr};
+use crate::selector::{DisplaySelector, connected_displays};
+
+fn manager() -> Result<MPDisplayMgr, Error> {
+    unsafe { MPDisplayMgr::shared().or_else(|| MPDisplayMgr::new()) }
+        .ok_or_else(|| Error::Platform("MonitorPanel manager not available".to_string()))
+}
+
+/// Resolve selectors to MonitorPanel displays from one manager
+fn resolve(mgr: &MPDisplayMgr, selectors: &[&str]) -> Result<Vec<(u32, MPDisplay)>, Error> {
+    let displays = connected_displays()?;
+    let mut resolved: Vec<(u32, MPDisplay)> = Vec::with_capacity(selectors.len());
+
+    for selector in selectors {
+        let display_id = selector
+            .parse::<DisplaySelector>()?
+            .select_one(&displays)?
+            .id;
+        if resolved.iter().any(|(id, _)| *id == display_id) {
+            return Err(Error::Invalid(format!(
+                "Display ID {} is named more than once",
+                display_id
+            )));
+        }
+        let mp_display = unsafe { mgr.display_with_id(display_id) }.ok_or_else(|| {
+            Error::NotFound(format!(
+                "Display ID {} not found in MonitorPanel",
+                display_id
+            ))
+        })?;
+        resolved.push((display_id, mp_display));
+    }
+    Ok(resolved)
+}
+
+/// IDs of the displays mirrored with `display`, including it
+fn mirror_set_ids(mgr: &MPDisplayMgr, display: &MPDisplay) -> Vec<u32> {
+    unsafe {
+        let mut ids: Vec<u32> = mgr
+            .mirror_set_for_display(display)
+            .unwrap_or_default()
+            .iter()
+            .map(|member| member.display_id() as u32)
+            .collect();
+        ids.sort_unstable();
+        ids
+    }
+}
+
+/// Mirror `source` onto `targets`, or onto every other display when `targets` is empty
+pub fn mirror_displays(source: &str, targets: &[String], best_mode: bool) {
+    println!("=== Mirroring Displays ===\n");
+
+    match mirror(source, targets, best_mode) {
+        Ok(members) => {
+            let members: Vec<String> = members.iter().map(|id| id.to_string()).collect();
+            println!("Mirror set: display IDs {}", members.join(", "));
+            println!("\n✓ Displays mirrored");
+        }
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn mirror(source: &str, targets: &[String], best_mode: bool) -> Result<Vec<u32>, Error> {
+    let mgr = manager()?;
+    let mut selectors = vec![source];
+    selectors.extend(targets.iter().map(String::as_str));
+    let displays = resolve(&mgr, &selectors)?;
+    let (source_id, source_display) = &displays[0];
+
+    println!("Source: display ID {}", source_id);
+    unsafe {
+        if displays.len() == 1 {
+            println!("Targets: all other displays");
+            mgr.mirror_all_displays_to(source_display, best_mode);
+        } else {
+            let targets: Vec<String> = displays[1..].iter().map(|(id, _)| id.to_string()).collect();
+            println!("Targets: display IDs {}", targets.join(", "));
+
+            let members: Vec<&MPDisplay> = displays.iter().map(|(_, display)| display).collect();
+            mgr.create_mirror_set(&members).ok_or_else(|| {
+                Error::Platform("Failed to build the list of displays to mirror".to_string())
+            })?;
+            if best_mode {
+                mgr.set_mirror_master(source_display, true);
+            }
+        }
+    }
+
+    let members = mirror_set_ids(&mgr, source_display);
+    if members.len() < 2 {
+        return Err(Error::Platform(format!(
+            "macOS did not mirror any display to display ID {}",
+            source_id
+        )));
+    }
+    let missing: Vec<String> = displays
+        .iter()
+        .filter(|(id, _)| !members.contains(id))
+        .map(|(id, _)| id.to_string())
+        .collect();
+    if !missing.is_empty() {
+        return Err(Error::Platform(format!(
+            "macOS did not mirror display ID(s) {}",
+            missing.join(", ")
+        )));
+    }
+    Ok(members)
+}
+
+/// Number of ranked resolutions shown by `mirror --plan`
+const PLAN_OPTIONS: usize = 5;
+
+/// Show the modes a mirror set would use without changing anything
+pub fn plan_mirror_displays(source: &str, targets: &[String]) {
+    println!("=== Mirror Plan ===\n");
+
+    if let Err(err) = plan(source, targets) {
+        eprintln!("Error: {}", err);
+        std::process::exit(1);
+    }
+}
+
+fn plan(source: &str, targets: &[String]) -> Result<(), Error> {
+    let mgr = manager()?;
+    let mut selectors: Vec<String> = vec![source.to_string()];
+    if targets.is_empty() {
+        let connected = connected_displays()?;
+        let source_id = source
+            .parse::<DisplaySelector>()?
+            .select_one(&connected)?
+            .id;
+        selectors.extend(
+            connected
+                .iter()
+                .filter(|display| display.id != source_id)
+                .map(|display| display.id.to_string()),
+        );
+    } else {
+        selectors.extend(targets.iter().cloned());
+    }
+    let selectors: Vec<&str> = selectors.iter().map(String::as_str).collect();
+    let displays = resolve(&mgr, &selectors)?;
+    if displays.len() < 2 {
+        return Err(Error::Invalid(
+            "Mirroring needs at least two displays".to_string(),
+        ));
+    }
+
+    let candidates: Vec<MirrorCandidate> = displays
+        .iter()
+        .map(|(display_id, display)| MirrorCandidate {
+            display_id: *display_id,
+            modes: unsafe { mode_snapshots(display) },
+        })
+        .collect();
+    let ids: Vec<String> = candidates
+        .iter()
+        .map(|c| c.display_id.to_string())
+        .collect();
+    println!("Displays: {}", ids.join(", "));
+
+    let plans = plan_mirror(&candidates);
+    let Some(best) = plans.first() else {
+        return Err(Error::NotFound(
+            "The displays have no resolution in common".to_string(),
+        ));
+    };
+
+    for (rank, option) in plans.iter().take(PLAN_OPTIONS).enumerate() {
+        println!(
+            "\n{}. {}x{} @ {}Hz{}",
+            rank + 1,
+            option.width,
+            option.height,
+            format_refresh_rate(option.refresh_rate),
+            if option.same_refresh {
+                ""
+            } else {
+                " (refresh rates differ)"
+            }
+        );
+        println!(
+            "   Aspect mismatch: {:.1}%, scaling: {:.1}%",
+            option.aspect_mismatch * 100.0,
+            option.scaling * 100.0
+        );
+        for choice in &option.choices {
+            println!(
+                "   Display ID {}: mode #{} {}",
+                choice.display_id, choice.mode.mode_number, choice.mode.descriptor
+            );
+        }
+    }
+    if plans.len() > PLAN_OPTIONS {
+        println!("\n({} more shared resolutions)", plans.len() - PLAN_OPTIONS);
+    }
+
+    println!("\n✓ Best shared resolution: {}x{}", best.width, best.height);
+    Ok(())
+}
+
+/// Stop mirroring one display, or all mirroring when `display` is `None`
+pub fn stop_mirroring(display: Option<&str>) {
+    println!("=== Stopping Mirroring ===\n");
+
+    let result = manager().and_then(|mgr| match display {
+        Some(selector) => {
+            let (display_id, mp_display) = resolve(&mgr, &[selector])?.remove(0);
+            if !unsafe { mp_display.is_mirrored() } {
+                return Err(Error::Invalid(format!(
+                    "Display ID {} is not mirrored",
+                    display_id
+                )));
+            }
+            unsafe { mgr.stop_mirroring_for_display(&mp_display) };
+            println!("Display ID: {}", display_id);
+            Ok(())
+        }
+        None => {
+            if unsafe { mgr.is_any_display_mirrored() } {
+                unsafe { mgr.stop_all_mirroring() };
+            } else {
+                println!("No displays are mirrored");
+            }
+            Ok(())
+        }
+    });
+
+    match result {
+        Ok(()) => println!("\n✓ Mirroring stopped"),
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            std::process::exit(1);
+        }
+    }
+}
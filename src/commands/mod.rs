@@ -9,6 +9,15 @@ pub use get_display_mode::get_display_mode;
 mod set_display_mode;
 pub use set_display_mode::set_display_mode;
 
+mod set_refresh;
+pub use set_refresh::set_refresh_rate;
+
+mod reset;
+pub use reset::{ResetOptions, reset_display};
+
+mod rescan;
+pub use rescan::{rescan, rescan_before_command};
+
 mod get_brightness;
 pub use get_brightness::get_brightness;
 
@@ -16,4 +25,43 @@ mod set_brightness;
 pub use set_brightness::set_brightness;
 
 mod watch;
-pub use watch::watch;
+pub use watch::{AutoProfileOptions, watch};
+
+mod profile;
+pub use profile::{
+    profile_apply, profile_delete, profile_export, profile_import, profile_list, profile_save,
+    profile_show,
+};
+
+mod apply_state;
+pub use apply_state::apply_desired_state;
+
+mod displayplacer;
+pub use displayplacer::{displayplacer_export, displayplacer_import};
+
+mod arrange;
+pub use arrange::{Position, arrange};
+
+mod layout;
+pub use layout::show_layout;
+
+mod set_main;
+pub use set_main::set_main;
+
+mod rotate;
+pub use rotate::rotate;
+
+mod underscan;
+pub use underscan::set_underscan;
+
+mod overscan;
+pub use overscan::set_overscan;
+
+mod hdr;
+pub use hdr::set_hdr;
+
+mod mirror;
+pub use mirror::{mirror_displays, plan_mirror_displays, stop_mirroring};
+
+mod preset;
+pub use preset::{preset_get, preset_list, preset_set};
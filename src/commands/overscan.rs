@@ -0,0 +1,33 @@
+use crate::error::Error;
+use crate::selector::resolve_display;
+
+pub fn set_overscan(display: &str, enabled: bool) {
+    println!("=== Setting Overscan ===\n");
+
+    match set_overscan_for(display, enabled) {
+        Ok(display_id) => {
+            println!("Display ID: {}", display_id);
+            println!("Overscan: {}", if enabled { "on" } else { "off" });
+            println!("\n✓ Overscan updated");
+        }
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn set_overscan_for(display: &str, enabled: bool) -> Result<u32, Error> {
+    let (display_id, mp_display) = resolve_display(display)?;
+
+    unsafe {
+        if !mp_display.supports_overscan() {
+            return Err(Error::Invalid(format!(
+                "Display ID {} does not support overscan",
+                display_id
+            )));
+        }
+        mp_display.set_overscan_enabled(enabled);
+    }
+    Ok(display_id)
+}
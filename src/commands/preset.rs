@@ -0,0 +1,133 @@
+use crate::error::Error;
+use crate::monitor_panel::{MPDisplay, MPDisplayPreset};
+use crate::preset::{activate_preset, active_preset_name, display_presets};
+use crate::selector::resolve_display;
+
+unsafe fn print_preset(preset: &MPDisplayPreset, markers: &[&str]) {
+    unsafe {
+        let name = preset.name().unwrap_or_else(|| "(unnamed)".to_string());
+        if markers.is_empty() {
+            println!("  {}", name);
+        } else {
+            println!("  {} [{}]", name, markers.join(", "));
+        }
+        if let Some(description) = preset.preset_description()
+            && !description.is_empty()
+        {
+            println!("    {}", description);
+        }
+        println!(
+            "    Group: {}, valid: {}, writable: {}",
+            preset.group(),
+            if preset.is_valid() { "yes" } else { "no" },
+            if preset.is_writable() { "yes" } else { "no" }
+        );
+    }
+}
+
+/// List the reference presets of a display
+pub fn preset_list(display: &str) {
+    println!("=== Display Presets ===\n");
+
+    if let Err(err) = list_presets(display) {
+        eprintln!("Error: {}", err);
+        std::process::exit(1);
+    }
+}
+
+fn list_presets(display: &str) -> Result<(), Error> {
+    let (display_id, mp_display) = resolve_display(display)?;
+    println!("Display ID: {}\n", display_id);
+
+    unsafe {
+        let presets = display_presets(&mp_display)?;
+        let active = mp_display.active_preset();
+        let default = mp_display.default_preset();
+        for preset in &presets {
+            let mut markers = Vec::new();
+            if active.as_ref().is_some_and(|active| active.same_as(preset)) {
+                markers.push("active");
+            }
+            if default
+                .as_ref()
+                .is_some_and(|default| default.same_as(preset))
+            {
+                markers.push("default");
+            }
+            print_preset(preset, &markers);
+        }
+    }
+    Ok(())
+}
+
+/// Show the active reference preset of a display
+pub fn preset_get(display: &str) {
+    println!("=== Active Preset ===\n");
+
+    match resolve_display(display).and_then(|(display_id, mp_display)| {
+        unsafe { display_presets(&mp_display) }?;
+        Ok((display_id, mp_display))
+    }) {
+        Ok((display_id, mp_display)) => {
+            println!("Display ID: {}", display_id);
+            unsafe { print_active(&mp_display) };
+        }
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            std::process::exit(1);
+        }
+    }
+}
+
+unsafe fn print_active(mp_display: &MPDisplay) {
+    unsafe {
+        match mp_display.active_preset() {
+            Some(preset) => {
+                println!("Active preset:");
+                print_preset(&preset, &[]);
+            }
+            None => println!("Active preset: none"),
+        }
+    }
+}
+
+/// Activate a reference preset by name
+pub fn preset_set(display: &str, name: &str) {
+    println!("=== Setting Preset ===\n");
+
+    match set_preset(display, name) {
+        Ok((display_id, previous, active)) => {
+            println!("Display ID: {}", display_id);
+            if previous == active {
+                println!("\n✓ Preset '{}' is already active", active);
+            } else {
+                println!("Preset: {} -> {}", previous, active);
+                println!("\n✓ Preset activated");
+            }
+        }
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn set_preset(display: &str, name: &str) -> Result<(u32, String, String), Error> {
+    let (display_id, mp_display) = resolve_display(display)?;
+
+    unsafe {
+        let previous = active_preset_name(&mp_display);
+        if !previous
+            .as_deref()
+            .is_some_and(|previous| previous.eq_ignore_ascii_case(name))
+        {
+            activate_preset(&mp_display, name)?;
+        }
+        let active = active_preset_name(&mp_display).unwrap_or_else(|| name.to_string());
+        Ok((
+            display_id,
+            previous.unwrap_or_else(|| "none".to_string()),
+            active,
+        ))
+    }
+}
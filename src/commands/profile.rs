@@ -0,0 +1,323 @@
+use std::path::Path;
+
+use crate::error::Error;
+use crate::gnome_monitors::{
+    GnomeConfiguration, parse_monitors_xml, render_monitors_xml, upsert_configuration,
+};
+use crate::linux_outputs::{
+    ExportFormat, ImportFormat, OutputConfig, merge_outputs, parse_kanshi, parse_sway, render,
+};
+use crate::mode_set::RetryPolicy;
+use crate::profile::{
+    Profile, apply_profile, capture_profile, delete_profile, list_profiles, load_profile,
+    save_profile,
+};
+
+pub fn profile_save(name: &str) {
+    println!("=== Saving Display Profile ===\n");
+
+    let result = capture_profile(name).and_then(|mut profile| {
+        // Keep Linux output names assigned to displays in an earlier save or import
+        if let Ok(existing) = load_profile(name) {
+            for state in profile.displays.iter_mut() {
+                state.output = existing
+                    .displays
+                    .iter()
+                    .find(|old| old.uuid.eq_ignore_ascii_case(&state.uuid))
+                    .and_then(|old| old.output.clone());
+            }
+        }
+        let path = save_profile(&profile)?;
+        Ok((profile, path))
+    });
+
+    match result {
+        Ok((profile, path)) => {
+            println!(
+                "✓ Saved profile '{}' with {} display(s) to {}",
+                profile.name,
+                profile.displays.len(),
+                path.display()
+            );
+        }
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            std::process::exit(1);
+        }
+    }
+}
+
+pub fn profile_apply(name: &str, closest: bool, force: bool, retry: RetryPolicy) {
+    println!("=== Applying Display Profile ===\n");
+
+    let result =
+        load_profile(name).and_then(|profile| apply_profile(&profile, closest, force, retry));
+
+    match result {
+        Ok(()) => println!("✓ Successfully applied profile '{}'", name),
+        Err(err) => {
+            eprintln!("✗ Failed to apply profile '{}': {}", name, err);
+            std::process::exit(1);
+        }
+    }
+}
+
+pub fn profile_list() {
+    match list_profiles() {
+        Ok(names) if names.is_empty() => {
+            println!("No saved profiles. Use 'profile save <name>' to create one");
+        }
+        Ok(names) => {
+            for name in names {
+                println!("{}", name);
+            }
+        }
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            std::process::exit(1);
+        }
+    }
+}
+
+pub fn profile_show(name: &str) {
+    let profile = match load_profile(name) {
+        Ok(profile) => profile,
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            std::process::exit(1);
+        }
+    };
+
+    println!("=== Profile '{}' ===\n", profile.name);
+
+    for (idx, state) in profile.displays.iter().enumerate() {
+        println!("Display {}:", idx + 1);
+        println!("  Persistent screen id: {}", state.uuid);
+        if let Some(name) = &state.name {
+            println!("  Name: {}", name);
+        }
+        if let Some(output) = &state.output {
+            println!("  Linux output: {}", output);
+        }
+        if let Some(mode) = &state.mode {
+            println!("  Mode: {}", mode);
+        }
+        if let Some(preset) = &state.preset {
+            println!("  Preset: {}", preset);
+        }
+        if let Some(hdr) = state.hdr {
+            println!("  HDR: {}", if hdr { "on" } else { "off" });
+        }
+        if let Some(brightness) = state.brightness {
+            println!("  Brightness: {}%", brightness);
+        }
+        if let Some(rotation) = state.rotation {
+            println!("  Rotation: {}°", rotation);
+        }
+        if let Some(origin) = state.origin {
+            println!("  Origin: ({}, {})", origin.x, origin.y);
+        }
+        println!();
+    }
+}
+
+pub fn profile_delete(name: &str) {
+    match delete_profile(name) {
+        Ok(()) => println!("✓ Deleted profile '{}'", name),
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Render a saved profile for a Linux display tool
+///
+/// `output_names` entries of the form `UUID=NAME` override the output name used for a
+/// display.
+pub fn profile_export(
+    name: &str,
+    format: ExportFormat,
+    output_names: &[String],
+    output: Option<&Path>,
+) {
+    let profile = match load_profile(name) {
+        Ok(profile) => profile,
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            std::process::exit(1);
+        }
+    };
+
+    let mut mappings = Vec::new();
+    for mapping in output_names {
+        let Some((uuid, output_name)) = mapping.split_once('=') else {
+            eprintln!(
+                "Error: Invalid output mapping '{}', expected UUID=NAME",
+                mapping
+            );
+            std::process::exit(1);
+        };
+        mappings.push((uuid.to_string(), output_name.to_string()));
+    }
+
+    let contents = if let Some(format) = format.output_format() {
+        let mut outputs = Vec::new();
+        for state in profile.displays.iter() {
+            let mut config = OutputConfig::from_display_state(state);
+            if let Some((_, output_name)) = mappings
+                .iter()
+                .find(|(uuid, _)| uuid.eq_ignore_ascii_case(&state.uuid))
+            {
+                config.name = output_name.clone();
+            }
+            outputs.push(config);
+        }
+        render(format, &profile.name, &outputs)
+    } else {
+        match gnome_export(&profile, &mappings, output) {
+            Ok(contents) => contents,
+            Err(err) => {
+                eprintln!("Error: {}", err);
+                std::process::exit(1);
+            }
+        }
+    };
+    match output {
+        Some(path) => {
+            if let Err(err) = std::fs::write(path, contents) {
+                eprintln!("Error: Failed to write {}: {}", path.display(), err);
+                std::process::exit(1);
+            }
+            println!("✓ Wrote profile '{}' to {}", profile.name, path.display());
+        }
+        None => print!("{}", contents),
+    }
+}
+
+/// Render a profile as monitors.xml
+///
+/// When writing over an existing monitors.xml, its other configurations are kept and only
+/// the one for the same set of monitors is replaced.
+fn gnome_export(
+    profile: &Profile,
+    mappings: &[(String, String)],
+    output: Option<&Path>,
+) -> Result<String, Error> {
+    let configuration = GnomeConfiguration::from_profile(profile, mappings)?;
+    let mut configurations = match output {
+        Some(path) if path.exists() => parse_monitors_xml(&std::fs::read_to_string(path)?)?,
+        _ => Vec::new(),
+    };
+    upsert_configuration(&mut configurations, configuration);
+    Ok(render_monitors_xml(&configurations))
+}
+
+/// Import kanshi, sway or GNOME output configuration into a profile
+///
+/// If the profile exists, matching displays are updated in place; otherwise a new
+/// profile is created.
+pub fn profile_import(
+    name: &str,
+    format: ImportFormat,
+    file: &Path,
+    kanshi_profile: Option<&str>,
+    gnome_configuration: Option<usize>,
+) {
+    let text = match std::fs::read_to_string(file) {
+        Ok(text) => text,
+        Err(err) => {
+            eprintln!("Error: Failed to read {}: {}", file.display(), err);
+            std::process::exit(1);
+        }
+    };
+
+    // Start a new profile only if none exists; a profile that fails to load is not replaced
+    let mut profile = match load_profile(name) {
+        Ok(profile) => profile,
+        Err(Error::NotFound(_)) => Profile {
+            name: name.to_string(),
+            displays: Vec::new(),
+        },
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            std::process::exit(1);
+        }
+    };
+
+    let imported = match format {
+        ImportFormat::Kanshi | ImportFormat::Sway => {
+            let outputs = if format == ImportFormat::Kanshi {
+                parse_kanshi(&text, kanshi_profile)
+            } else {
+                parse_sway(&text)
+            };
+            outputs.map(|outputs| {
+                merge_outputs(&mut profile, &outputs);
+                outputs.len()
+            })
+        }
+        ImportFormat::Gnome => gnome_import(&mut profile, &text, gnome_configuration),
+    };
+    let imported = match imported {
+        Ok(imported) => imported,
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            std::process::exit(1);
+        }
+    };
+
+    match save_profile(&profile) {
+        Ok(path) => println!(
+            "✓ Imported {} output(s) into profile '{}' at {}",
+            imported,
+            name,
+            path.display()
+        ),
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Merge one monitors.xml configuration into a profile, returning the number of monitors
+fn gnome_import(
+    profile: &mut Profile,
+    text: &str,
+    configuration: Option<usize>,
+) -> Result<usize, Error> {
+    let configurations = parse_monitors_xml(text)?;
+
+    let selected = match configuration {
+        Some(number) => configurations.get(number.wrapping_sub(1)).ok_or_else(|| {
+            Error::NotFound(format!(
+                "monitors.xml has {} configuration(s), not {}",
+                configurations.len(),
+                number
+            ))
+        })?,
+        None => {
+            let mut identities: Vec<_> = profile
+                .displays
+                .iter()
+                .filter_map(|state| state.monitor.as_ref())
+                .collect();
+            identities.sort_by(|a, b| {
+                (&a.vendor, &a.product, &a.serial).cmp(&(&b.vendor, &b.product, &b.serial))
+            });
+            configurations
+                .iter()
+                .find(|candidate| !identities.is_empty() && candidate.identities() == identities)
+                .or_else(|| configurations.first())
+                .ok_or_else(|| Error::NotFound("monitors.xml has no configurations".to_string()))?
+        }
+    };
+
+    selected.merge_into(profile);
+    Ok(selected
+        .logical_monitors
+        .iter()
+        .map(|logical| logical.monitors.len())
+        .sum())
+}
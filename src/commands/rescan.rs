@@ -0,0 +1,36 @@
+use crate::rescan::rescan_displays;
+
+/// Rescan displays and modes and report what changed
+pub fn rescan() {
+    println!("=== Rescanning Displays ===\n");
+
+    match rescan_displays() {
+        Ok(changes) if changes.is_empty() => println!("✓ No changes"),
+        Ok(changes) => {
+            for change in &changes {
+                println!("{}", change);
+            }
+            println!("\n✓ {} change(s) found", changes.len());
+        }
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Rescan before running another command, reporting changes on stderr so that
+/// machine-readable output stays clean
+pub fn rescan_before_command() {
+    match rescan_displays() {
+        Ok(changes) => {
+            for change in &changes {
+                eprintln!("Rescan: {}", change);
+            }
+        }
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            std::process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,137 @@
+use crate::brightness::{CoreDisplay_Display_SetUserBrightness, user_brightness};
+use crate::error::Error;
+use crate::mode_descriptor::ModeDescriptor;
+use crate::mode_safety::check_mode_change;
+use crate::mode_set::{RetryPolicy, set_mode};
+use crate::monitor_panel::MPDisplay;
+use crate::rotation::rotate_display;
+use crate::selector::resolve_display;
+
+/// Brightness restored by `reset --brightness`, as a fraction
+const DEFAULT_BRIGHTNESS: f64 = 1.0;
+
+/// Settings besides the mode that `reset` should also restore
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ResetOptions {
+    /// Use the native mode instead of the default mode
+    pub native: bool,
+    pub brightness: bool,
+    pub underscan: bool,
+    pub rotation: bool,
+    /// Change the mode even in safe mode or when the mode is hidden, interlaced or stretched
+    pub force: bool,
+    /// Retries of transient mode change failures
+    pub retry: RetryPolicy,
+}
+
+/// Return a display to its default (or native) mode and, optionally, default settings
+pub fn reset_display(display: &str, options: ResetOptions) {
+    println!("=== Resetting Display ===\n");
+
+    match reset(display, options) {
+        Ok((display_id, changes)) => {
+            println!("Display ID: {}", display_id);
+            if changes.is_empty() {
+                println!("\n✓ Display is already reset");
+            } else {
+                for change in &changes {
+                    println!("{}", change);
+                }
+                println!("\n✓ Display reset");
+            }
+        }
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn reset(display: &str, options: ResetOptions) -> Result<(u32, Vec<String>), Error> {
+    let (display_id, mp_display) = resolve_display(display)?;
+    let mut changes = Vec::new();
+
+    unsafe {
+        if let Some(change) = reset_mode(display_id, &mp_display, options)? {
+            changes.push(change);
+        }
+
+        if options.rotation {
+            let previous = mp_display.orientation();
+            if previous != 0 {
+                rotate_display(&mp_display, 0)?;
+                changes.push(format!("Rotation: {}° -> 0°", previous));
+            }
+        }
+
+        if options.underscan && mp_display.supports_underscan() {
+            let previous = mp_display.underscan();
+            let value = 0.clamp(mp_display.min_underscan(), mp_display.max_underscan());
+            if previous != value {
+                mp_display.set_underscan(value);
+                changes.push(format!("Underscan: {} -> {}", previous, value));
+            }
+        }
+
+        // Displays without adjustable brightness are left alone, like underscan above
+        if options.brightness
+            && let Some(previous) = user_brightness(display_id)
+            && previous != DEFAULT_BRIGHTNESS
+        {
+            CoreDisplay_Display_SetUserBrightness(display_id, DEFAULT_BRIGHTNESS);
+            changes.push(format!(
+                "Brightness: {}% -> {}%",
+                (previous * 100.0).round(),
+                (DEFAULT_BRIGHTNESS * 100.0).round()
+            ));
+        }
+    }
+
+    Ok((display_id, changes))
+}
+
+/// Switch to the default or native mode, describing the change if one was made
+unsafe fn reset_mode(
+    display_id: u32,
+    mp_display: &MPDisplay,
+    options: ResetOptions,
+) -> Result<Option<String>, Error> {
+    unsafe {
+        let (target, kind) = if options.native {
+            (mp_display.native_mode(), "native")
+        } else {
+            (mp_display.default_mode(), "default")
+        };
+        let target = target.ok_or_else(|| {
+            Error::NotFound(format!(
+                "Display ID {} reports no {} mode",
+                display_id, kind
+            ))
+        })?;
+
+        let mode_number = target.mode_number();
+        let current = mp_display.current_mode();
+        if current.as_ref().map(|mode| mode.mode_number()) == Some(mode_number) {
+            return Ok(None);
+        }
+
+        check_mode_change(mp_display, mode_number, options.force)?;
+        set_mode(mp_display, mode_number, options.retry).map_err(|err| {
+            Error::Platform(format!(
+                "Failed to set display ID {} to its {} mode #{}: {}",
+                display_id, kind, mode_number, err
+            ))
+        })?;
+
+        let previous = current
+            .map(|mode| ModeDescriptor::from_mode(mp_display, &mode).to_string())
+            .unwrap_or_else(|| "unknown".to_string());
+        Ok(Some(format!(
+            "Mode: {} -> {} mode #{} {}",
+            previous,
+            kind,
+            mode_number,
+            ModeDescriptor::from_mode(mp_display, &target)
+        )))
+    }
+}
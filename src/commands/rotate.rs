@@ -0,0 +1,35 @@
+use crate::error::Error;
+use crate::rotation::rotate_display;
+use crate::selector::resolve_display;
+
+pub fn rotate(display: &str, degrees: i32) {
+    println!("=== Rotating Display ===\n");
+
+    match rotate_selected(display, degrees) {
+        Ok((display_id, previous)) => {
+            println!("Display ID: {}", display_id);
+            if previous == degrees {
+                println!("\n✓ Display is already rotated {}°", degrees);
+            } else {
+                println!("Rotation: {}° -> {}°", previous, degrees);
+                println!("\n✓ Display rotated");
+            }
+        }
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn rotate_selected(display: &str, degrees: i32) -> Result<(u32, i32), Error> {
+    let (display_id, mp_display) = resolve_display(display)?;
+
+    unsafe {
+        let previous = mp_display.orientation();
+        if previous != degrees {
+            rotate_display(&mp_display, degrees)?;
+        }
+        Ok((display_id, previous))
+    }
+}
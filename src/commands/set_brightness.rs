@@ -1,56 +1,40 @@
-use crate::monitor_panel::MPDisplayMgr;
+use crate::brightness::{CoreDisplay_Display_SetUserBrightness, user_brightness};
+use crate::display_name::display_label;
+use crate::selector::resolve_display;
 use core_graphics::display::CGDisplay;
 
-// Link to CoreDisplay framework for brightness functions
-#[link(name = "CoreDisplay", kind = "framework")]
-unsafe extern "C" {
-    fn CoreDisplay_Display_SetUserBrightness(display: u32, brightness: f64);
-    fn CoreDisplay_Display_GetUserBrightness(display: u32) -> f64;
-}
-
-pub fn set_brightness(display_id: u32, brightness: u32) {
+pub fn set_brightness(display: &str, brightness: u32) {
     // Validate brightness percentage
     if brightness > 100 {
         eprintln!("Error: Brightness must be between 0 and 100");
         std::process::exit(1);
     }
 
-    // Get list of active displays
-    let displays = CGDisplay::active_displays().expect("Failed to get displays");
-
-    // Check if display exists
-    if !displays.contains(&display_id) {
-        eprintln!("Error: Display ID {} not found", display_id);
-        std::process::exit(1);
-    }
-
+    let (display_id, mp_display) = match resolve_display(display) {
+        Ok(resolved) => resolved,
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            std::process::exit(1);
+        }
+    };
     let display = CGDisplay::new(display_id);
 
     println!("=== Setting Display Brightness ===\n");
     println!("Display ID: {}", display_id);
 
     // Get persistent screen ID from MonitorPanel for display info
-    unsafe {
-        if let Some(mgr) = MPDisplayMgr::new().or_else(|| MPDisplayMgr::shared()) {
-            if let Some(mp_displays) = mgr.displays() {
-                for mp_display in mp_displays.iter() {
-                    if mp_display.display_id() == display_id as i32 {
-                        if let Some(uuid) = mp_display.uuid() {
-                            println!("Persistent screen id: {}", uuid);
-                        }
-                        break;
-                    }
-                }
-            }
-        }
+    if let Some(uuid) = unsafe { mp_display.uuid() } {
+        println!("Persistent screen id: {}", uuid);
     }
 
+    if let Some(label) = display_label(display_id) {
+        label.print("");
+    }
     println!("Display Model: {}", display.model_number());
     println!("Is built-in: {}", display.is_builtin());
 
     // Get current brightness before setting
-    let current_brightness = unsafe { CoreDisplay_Display_GetUserBrightness(display_id) };
-    if current_brightness >= 0.0 && current_brightness <= 1.0 {
+    if let Some(current_brightness) = user_brightness(display_id) {
         let current_percentage = (current_brightness * 100.0).round() as u32;
         println!("Current brightness: {}%", current_percentage);
     }
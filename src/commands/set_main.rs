@@ -0,0 +1,32 @@
+use crate::arrangement::{Move, set_main_display};
+use crate::error::Error;
+use crate::selector::{DisplaySelector, connected_displays};
+
+pub fn set_main(display: &str) {
+    println!("=== Setting Main Display ===\n");
+
+    match set_main_for(display) {
+        Ok((display_id, moves)) => {
+            println!("Display ID: {}", display_id);
+            if moves.is_empty() {
+                println!("\n✓ Display is already the main display");
+                return;
+            }
+            for m in moves.iter() {
+                println!("Display ID {}: {} -> {}", m.display_id, m.from, m.to);
+            }
+            println!("\n✓ Main display changed");
+        }
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn set_main_for(display: &str) -> Result<(u32, Vec<Move>), Error> {
+    let displays = connected_displays()?;
+    let selector: DisplaySelector = display.parse()?;
+    let display_id = selector.select_one(&displays)?.id;
+    Ok((display_id, set_main_display(display_id)?))
+}
@@ -0,0 +1,85 @@
+use crate::error::Error;
+use crate::mode_descriptor::{ModeDescriptor, ModeSnapshot, format_refresh_rate};
+use crate::mode_safety::{check_mode_safety, running_in_safe_mode};
+use crate::mode_set::{RetryPolicy, set_mode};
+use crate::selector::resolve_display;
+
+pub fn set_refresh_rate(display: &str, refresh_rate: f64, force: bool, retry: RetryPolicy) {
+    println!("=== Setting Refresh Rate ===\n");
+
+    match set_refresh_for(display, refresh_rate, force, retry) {
+        Ok((display_id, previous, current)) => {
+            println!("Display ID: {}", display_id);
+            if previous.matches(&current) {
+                println!(
+                    "\n✓ Display is already at {}Hz",
+                    format_refresh_rate(current.refresh_rate)
+                );
+            } else {
+                println!(
+                    "Refresh rate: {}Hz -> {}Hz",
+                    format_refresh_rate(previous.refresh_rate),
+                    format_refresh_rate(current.refresh_rate)
+                );
+                println!("Mode: {}", current);
+                println!("\n✓ Refresh rate changed");
+            }
+        }
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Switch to the mode with the current resolution and scale at `refresh_rate`
+fn set_refresh_for(
+    display: &str,
+    refresh_rate: f64,
+    force: bool,
+    retry: RetryPolicy,
+) -> Result<(u32, ModeDescriptor, ModeDescriptor), Error> {
+    if !refresh_rate.is_finite() || refresh_rate <= 0.0 {
+        return Err(Error::Invalid(format!(
+            "Refresh rate must be a positive number of Hz, not {}",
+            refresh_rate
+        )));
+    }
+
+    let (display_id, mp_display) = resolve_display(display)?;
+
+    unsafe {
+        let current_mode = mp_display.current_mode().ok_or_else(|| {
+            Error::Platform(format!(
+                "Could not read the current mode of display ID {}",
+                display_id
+            ))
+        })?;
+        let previous = ModeDescriptor::from_mode(&mp_display, &current_mode);
+        let variants: Vec<ModeSnapshot> = mp_display
+            .modes_matching_resolution_of_mode(&current_mode)
+            .unwrap_or_default()
+            .iter()
+            .map(|mode| ModeSnapshot::from_mode(&mp_display, mode))
+            .collect();
+
+        let mode_number = previous.find_refresh_rate(&variants, refresh_rate)?;
+        if mode_number != current_mode.mode_number() {
+            if let Some(mode) = variants.iter().find(|mode| mode.mode_number == mode_number) {
+                check_mode_safety(mode, running_in_safe_mode(), force)?;
+            }
+            set_mode(&mp_display, mode_number, retry).map_err(|err| {
+                Error::Platform(format!(
+                    "Failed to set display ID {} to mode #{}: {}",
+                    display_id, mode_number, err
+                ))
+            })?;
+        }
+
+        let current = mp_display
+            .current_mode()
+            .map(|mode| ModeDescriptor::from_mode(&mp_display, &mode))
+            .unwrap_or_else(|| previous.clone());
+        Ok((display_id, previous, current))
+    }
+}
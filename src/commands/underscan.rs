@@ -0,0 +1,43 @@
+use crate::error::Error;
+use crate::selector::resolve_display;
+
+pub fn set_underscan(display: &str, value: i32) {
+    println!("=== Setting Underscan ===\n");
+
+    match set_underscan_for(display, value) {
+        Ok((display_id, previous)) => {
+            println!("Display ID: {}", display_id);
+            println!("Underscan: {} -> {}", previous, value);
+            println!("\n✓ Underscan updated");
+        }
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn set_underscan_for(display: &str, value: i32) -> Result<(u32, i32), Error> {
+    let (display_id, mp_display) = resolve_display(display)?;
+
+    unsafe {
+        if !mp_display.supports_underscan() {
+            return Err(Error::Invalid(format!(
+                "Display ID {} does not support underscan",
+                display_id
+            )));
+        }
+
+        let (min, max) = (mp_display.min_underscan(), mp_display.max_underscan());
+        if !(min..=max).contains(&value) {
+            return Err(Error::Invalid(format!(
+                "Underscan for display ID {} must be between {} and {}",
+                display_id, min, max
+            )));
+        }
+
+        let previous = mp_display.underscan();
+        mp_display.set_underscan(value);
+        Ok((display_id, previous))
+    }
+}
@@ -1,10 +1,22 @@
 use std::collections::HashMap;
 use std::os::raw::c_void;
 use std::ptr;
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
 
 use cocoa::appkit::NSApp;
 use core_graphics::display::CGDisplay;
 
+use crate::error::Error;
+use crate::mode_set::RetryPolicy;
+use crate::monitor_panel::MPDisplayMgr;
+use crate::profile::{
+    apply_profile, connected_display_uuids, find_matching_profile, fingerprint, load_profile,
+};
+use crate::reconfigure_lock::ReconfigureLock;
+use crate::rescan::refresh_display_list;
+
 /// Native watcher using CGDisplayRegisterReconfigurationCallback with a polling fallback.
 ///
 /// The CLI prints diagnostics at startup, registers the CoreGraphics callback, then starts
@@ -13,6 +25,12 @@ use core_graphics::display::CGDisplay;
 type CGDirectDisplayID = u32;
 type CGDisplayChangeSummaryFlags = u32;
 
+/// Opaque libdispatch queue
+#[repr(C)]
+struct DispatchQueue {
+    _private: [u8; 0],
+}
+
 unsafe extern "C" {
     // void CGDisplayRegisterReconfigurationCallback(CGDisplayReconfigurationCallBack callback, void *userInfo);
     fn CGDisplayRegisterReconfigurationCallback(
@@ -27,8 +45,77 @@ unsafe extern "C" {
 
     // Run the CoreFoundation run loop so system-delivered callbacks are invoked.
     fn CFRunLoopRun();
+
+    // The main queue, drained by the main thread's run loop; dispatch_get_main_queue()
+    // is a macro for its address.
+    static _dispatch_main_q: DispatchQueue;
+    // void dispatch_sync_f(dispatch_queue_t queue, void *context, dispatch_function_t work);
+    fn dispatch_sync_f(
+        queue: *const DispatchQueue,
+        context: *mut c_void,
+        work: extern "C" fn(*mut c_void),
+    );
 }
 
+/// Run `work` on the main thread and wait for its result.
+///
+/// MonitorPanel is driven from the thread that owns the run loop, which `watch` runs on
+/// the main thread; the auto-profile thread only decides when to act.
+fn on_main_thread<F: FnOnce() -> T, T>(work: F) -> T {
+    struct Job<F, T> {
+        work: Option<F>,
+        result: Option<T>,
+    }
+
+    extern "C" fn run<F: FnOnce() -> T, T>(context: *mut c_void) {
+        let job = unsafe { &mut *(context as *mut Job<F, T>) };
+        if let Some(work) = job.work.take() {
+            job.result = Some(work());
+        }
+    }
+
+    let mut job = Job {
+        work: Some(work),
+        result: None,
+    };
+    unsafe {
+        dispatch_sync_f(
+            &raw const _dispatch_main_q,
+            (&mut job as *mut Job<F, T>).cast(),
+            run::<F, T>,
+        );
+    }
+    job.result
+        .expect("dispatch_sync_f runs the job before returning")
+}
+
+/// Persistent screen ids of the connected displays, after making the shared manager
+/// pick up displays connected since it was created
+fn refreshed_display_uuids() -> Result<Vec<String>, Error> {
+    if let Some(mgr) = unsafe { MPDisplayMgr::shared() } {
+        unsafe { refresh_display_list(&mgr) };
+    }
+    connected_display_uuids()
+}
+
+/// Options for automatically applying saved profiles when the display set changes.
+pub struct AutoProfileOptions {
+    /// Profile to apply when no saved profile matches the connected displays
+    pub fallback: Option<String>,
+    /// How long the configuration must stay quiet before a profile is chosen
+    pub settle: Duration,
+    /// Allow mode changes that would otherwise be refused as unsafe
+    pub force: bool,
+    /// How long to wait for another displayconfig that is reconfiguring before skipping
+    /// the profile; `None` skips straight away
+    pub wait: Option<Duration>,
+    /// Retries of transient mode change failures
+    pub retry: RetryPolicy,
+}
+
+/// Time of the most recent reconfiguration callback not yet seen by the auto-profile thread.
+static PENDING_CHANGE: Mutex<Option<Instant>> = Mutex::new(None);
+
 extern "C" fn display_reconfig_callback(
     display: CGDirectDisplayID,
     flags: CGDisplayChangeSummaryFlags,
@@ -52,6 +139,10 @@ extern "C" fn display_reconfig_callback(
     } else {
         println!("  Current mode: (none)");
     }
+
+    if let Ok(mut pending) = PENDING_CHANGE.lock() {
+        *pending = Some(Instant::now());
+    }
 }
 
 /// Capture display state (mode triple) for fallback polling.
@@ -72,9 +163,110 @@ fn capture_state() -> HashMap<u32, Option<(u64, u64, f64)>> {
     map
 }
 
+/// Choose and apply the profile for the currently connected displays.
+///
+/// Does nothing if the display set is the one handled last time, so the reconfiguration
+/// caused by applying a profile does not trigger it again. A display set only counts as
+/// handled once its profile is applied (or none matches), so failures are retried after
+/// the next reconfiguration. Displays are read and the profile applied on the main thread.
+fn auto_apply_profile(options: &AutoProfileOptions, handled: &mut Option<String>) {
+    let uuids = match on_main_thread(refreshed_display_uuids) {
+        Ok(uuids) => uuids,
+        Err(err) => {
+            eprintln!("[auto-profile] Error: {}", err);
+            return;
+        }
+    };
+
+    let current = fingerprint(&uuids);
+    if handled.as_deref() == Some(current.as_str()) {
+        return;
+    }
+
+    println!(
+        "[auto-profile] Display set settled: {} display(s) [{}]",
+        uuids.len(),
+        current
+    );
+
+    let (profile, rule) = match find_matching_profile(&uuids) {
+        Ok(Some(profile)) => (profile, "exact match"),
+        Ok(None) => match &options.fallback {
+            Some(name) => match load_profile(name) {
+                Ok(profile) => (profile, "fallback"),
+                Err(err) => {
+                    eprintln!("[auto-profile] Error loading fallback profile: {}", err);
+                    return;
+                }
+            },
+            None => {
+                println!("[auto-profile] No profile matches this display set; nothing applied");
+                *handled = Some(current);
+                return;
+            }
+        },
+        Err(err) => {
+            eprintln!("[auto-profile] Error: {}", err);
+            return;
+        }
+    };
+
+    println!(
+        "[auto-profile] Rule '{}' fired: applying profile '{}'",
+        rule, profile.name
+    );
+    let _lock = match ReconfigureLock::acquire(options.wait) {
+        Ok(lock) => lock,
+        Err(err) => {
+            eprintln!(
+                "[auto-profile] Skipping profile '{}': {}",
+                profile.name, err
+            );
+            return;
+        }
+    };
+    match on_main_thread(|| apply_profile(&profile, false, options.force, options.retry)) {
+        Ok(()) => {
+            println!("[auto-profile] ✓ Applied profile '{}'", profile.name);
+            *handled = Some(current);
+        }
+        Err(err) => eprintln!(
+            "[auto-profile] ✗ Failed to apply profile '{}': {}",
+            profile.name, err
+        ),
+    }
+}
+
+/// Apply the profile for the startup display set, then re-evaluate whenever
+/// reconfiguration callbacks have been quiet for the settle delay.
+fn auto_profile_loop(options: AutoProfileOptions) {
+    let mut handled: Option<String> = None;
+    auto_apply_profile(&options, &mut handled);
+
+    loop {
+        thread::sleep(Duration::from_millis(250));
+
+        let settled = match PENDING_CHANGE.lock() {
+            Ok(mut pending) => match *pending {
+                Some(changed_at) if changed_at.elapsed() >= options.settle => {
+                    *pending = None;
+                    true
+                }
+                _ => false,
+            },
+            Err(_) => false,
+        };
+
+        if settled {
+            auto_apply_profile(&options, &mut handled);
+        }
+    }
+}
+
 /// Register the native reconfiguration callback and keep the process alive.
-/// The CLI's interval is used for the polling fallback only (callback is immediate).
-pub fn watch() {
+/// With `auto_profile`, a background thread waits for changes to settle and has the main
+/// thread apply the saved profile matching the connected displays.
+pub fn watch(auto_profile: Option<AutoProfileOptions>) {
     println!(
         "Watching for display configuration changes via CGDisplayRegisterReconfigurationCallback..."
     );
@@ -86,6 +278,15 @@ pub fn watch() {
         initial.keys().collect::<Vec<_>>()
     );
 
+    if let Some(options) = auto_profile {
+        println!(
+            "Auto-profile enabled (settle delay: {}ms, fallback: {})",
+            options.settle.as_millis(),
+            options.fallback.as_deref().unwrap_or("none")
+        );
+        thread::spawn(move || auto_profile_loop(options));
+    }
+
     unsafe {
         // Register callback; user_info is null for now.
         CGDisplayRegisterReconfigurationCallback(display_reconfig_callback, ptr::null_mut());
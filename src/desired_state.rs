@@ -0,0 +1,375 @@
+// Declarative desired display state
+// `apply -f desk.toml` compares a desired configuration against the connected displays
+// and changes only what differs, so it can be run repeatedly from configuration
+// management tools.
+
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use core_graphics::display::CGDisplay;
+use serde::{Deserialize, Serialize};
+
+use crate::arrangement::arrange_displays;
+use crate::brightness::{CoreDisplay_Display_SetUserBrightness, user_brightness};
+use crate::error::Error;
+use crate::layout::Origin;
+use crate::mode_descriptor::{ModeDescriptor, ModeSnapshot, format_refresh_rate, mode_snapshots};
+use crate::mode_safety::{check_mode_safety, running_in_safe_mode};
+use crate::mode_set::{RetryPolicy, set_mode};
+use crate::monitor_panel::MPDisplayMgr;
+use crate::rotation::{check_rotation, rotate_display};
+use crate::selector::{DisplaySelector, connected_displays};
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct DesiredState {
+    #[serde(default, rename = "display")]
+    pub displays: Vec<DesiredDisplay>,
+}
+
+/// Desired settings for the displays matched by a selector
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DesiredDisplay {
+    /// Display selector (`main`, `builtin`, `external`, a kind tag such as
+    /// `tv` or `tag:4k`, `id:N`, a UUID or `name:…`)
+    pub selector: String,
+    /// Brightness percentage (0-100)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub brightness: Option<u32>,
+    /// Rotation in degrees (0, 90, 180 or 270)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rotation: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mode: Option<ModeRequest>,
+    /// Top-left corner in the global display coordinate space
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub origin: Option<Origin>,
+}
+
+/// A mode described by the properties that matter to the user; unset fields match anything
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct ModeRequest {
+    pub width: i32,
+    pub height: i32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refresh_rate: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scale: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hidpi: Option<bool>,
+    /// Color depth as reported by the mode description
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub depth: Option<i32>,
+}
+
+impl ModeRequest {
+    pub fn accepts(&self, mode: &ModeDescriptor) -> bool {
+        mode.width == self.width
+            && mode.height == self.height
+            && self
+                .refresh_rate
+                .is_none_or(|rate| (mode.refresh_rate - rate).abs() < 0.01)
+            && self
+                .scale
+                .is_none_or(|scale| (mode.scale - scale).abs() < 0.01)
+            && self.hidpi.is_none_or(|hidpi| mode.hidpi == hidpi)
+            && match (self.depth, mode.depth) {
+                (Some(wanted), Some(depth)) => wanted == depth,
+                _ => true,
+            }
+    }
+
+    /// Pick the mode satisfying this request
+    ///
+    /// The current mode wins if it already satisfies the request, so repeated runs are
+    /// no-ops. Otherwise user-visible modes are preferred, then the highest refresh rate.
+    pub fn choose<'a>(
+        &self,
+        modes: &'a [ModeSnapshot],
+        current: Option<i32>,
+    ) -> Option<&'a ModeSnapshot> {
+        let candidates = modes.iter().filter(|mode| self.accepts(&mode.descriptor));
+        if let Some(current) = current
+            && let Some(mode) = candidates.clone().find(|mode| mode.mode_number == current)
+        {
+            return Some(mode);
+        }
+        candidates.max_by(|a, b| {
+            a.user_visible
+                .cmp(&b.user_visible)
+                .then(
+                    a.descriptor
+                        .refresh_rate
+                        .total_cmp(&b.descriptor.refresh_rate),
+                )
+                .then(b.mode_number.cmp(&a.mode_number))
+        })
+    }
+}
+
+impl fmt::Display for ModeRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}x{}", self.width, self.height)?;
+        if let Some(rate) = self.refresh_rate {
+            write!(f, " @ {}Hz", format_refresh_rate(rate))?;
+        }
+        if let Some(scale) = self.scale {
+            write!(f, " scale={:.1}x", scale)?;
+        }
+        if let Some(depth) = self.depth {
+            write!(f, " depth={}", depth)?;
+        }
+        match self.hidpi {
+            Some(true) => write!(f, " [HiDPI]"),
+            Some(false) => write!(f, " [non-HiDPI]"),
+            None => Ok(()),
+        }
+    }
+}
+
+/// One property of one display that differs from the desired state
+#[derive(Serialize, Clone, Debug)]
+pub struct Change {
+    /// Persistent screen id, or `id:N` when the display has none
+    pub display: String,
+    /// Selector from the desired state that matched the display
+    pub selector: String,
+    pub property: String,
+    pub current: String,
+    pub desired: String,
+    #[serde(skip)]
+    action: Action,
+}
+
+#[derive(Clone, Debug)]
+enum Action {
+    Mode { display_id: u32, mode_number: i32 },
+    Brightness { display_id: u32, brightness: u32 },
+    Rotation { display_id: u32, degrees: i32 },
+    Origin { display_id: u32, origin: Origin },
+}
+
+impl DesiredState {
+    /// Read a desired state file
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        let contents = fs::read_to_string(path)?;
+        toml::from_str(&contents)
+            .map_err(|err| Error::Format(format!("Failed to parse {}: {}", path.display(), err)))
+    }
+
+    /// Compare the desired state with the connected displays
+    ///
+    /// Every selector must match at least one display. Returns the changes needed to
+    /// reach the desired state, in the order `execute` applies them with origin changes
+    /// last; an empty list means the displays are compliant. Unsafe mode changes are
+    /// refused unless `force` is set.
+    pub fn plan(&self, force: bool) -> Result<Vec<Change>, Error> {
+        let displays = connected_displays()?;
+        let mgr = unsafe { MPDisplayMgr::shared().or_else(|| MPDisplayMgr::new()) }
+            .ok_or_else(|| Error::Platform("MonitorPanel manager not available".to_string()))?;
+
+        let mut changes = Vec::new();
+
+        for desired in self.displays.iter() {
+            let selector: DisplaySelector = desired.selector.parse()?;
+            let matched = selector.select(&displays);
+            if matched.is_empty() {
+                return Err(Error::NotFound(format!(
+                    "Selector '{}' matched no connected display",
+                    desired.selector
+                )));
+            }
+
+            for facts in matched {
+                let display = facts
+                    .uuid
+                    .clone()
+                    .unwrap_or_else(|| format!("id:{}", facts.id));
+
+                let mp_display = unsafe { mgr.display_with_id(facts.id) }.ok_or_else(|| {
+                    Error::NotFound(format!("Display {} not found in MonitorPanel", display))
+                })?;
+
+                if let Some(request) = &desired.mode {
+                    let current = unsafe { mp_display.current_mode() };
+                    let current_number = current.as_ref().map(|mode| unsafe { mode.mode_number() });
+                    let modes = unsafe { mode_snapshots(&mp_display) };
+
+                    let chosen = request.choose(&modes, current_number).ok_or_else(|| {
+                        Error::NotFound(format!(
+                            "Display {} has no mode matching {}",
+                            display, request
+                        ))
+                    })?;
+
+                    if current_number != Some(chosen.mode_number) {
+                        check_mode_safety(chosen, unsafe { running_in_safe_mode() }, force)
+                            .map_err(|err| {
+                                Error::Invalid(format!("Display {}: {}", display, err))
+                            })?;
+                        changes.push(Change {
+                            display: display.clone(),
+                            selector: desired.selector.clone(),
+                            property: "mode".to_string(),
+                            current: current
+                                .map(|mode| {
+                                    unsafe { ModeDescriptor::from_mode(&mp_display, &mode) }
+                                        .to_string()
+                                })
+                                .unwrap_or_else(|| "unknown".to_string()),
+                            desired: chosen.descriptor.to_string(),
+                            action: Action::Mode {
+                                display_id: facts.id,
+                                mode_number: chosen.mode_number,
+                            },
+                        });
+                    }
+                }
+
+                if let Some(brightness) = desired.brightness {
+                    if brightness > 100 {
+                        return Err(Error::Invalid(format!(
+                            "Brightness for '{}' must be between 0 and 100",
+                            desired.selector
+                        )));
+                    }
+
+                    let current = user_brightness(facts.id).ok_or_else(|| {
+                        Error::Platform(format!(
+                            "Brightness is not available for display {}",
+                            display
+                        ))
+                    })?;
+
+                    let current = (current * 100.0).round() as u32;
+                    if current != brightness {
+                        changes.push(Change {
+                            display: display.clone(),
+                            selector: desired.selector.clone(),
+                            property: "brightness".to_string(),
+                            current: format!("{}%", current),
+                            desired: format!("{}%", brightness),
+                            action: Action::Brightness {
+                                display_id: facts.id,
+                                brightness,
+                            },
+                        });
+                    }
+                }
+
+                if let Some(degrees) = desired.rotation {
+                    let current = unsafe { mp_display.orientation() };
+                    if current != degrees {
+                        unsafe { check_rotation(&mp_display, degrees) }.map_err(|err| {
+                            Error::Invalid(format!("'{}': {}", desired.selector, err))
+                        })?;
+                        changes.push(Change {
+                            display: display.clone(),
+                            selector: desired.selector.clone(),
+                            property: "rotation".to_string(),
+                            current: format!("{}°", current),
+                            desired: format!("{}°", degrees),
+                            action: Action::Rotation {
+                                display_id: facts.id,
+                                degrees,
+                            },
+                        });
+                    }
+                }
+
+                if let Some(origin) = desired.origin {
+                    let bounds = CGDisplay::new(facts.id).bounds();
+                    let current = Origin {
+                        x: bounds.origin.x as i32,
+                        y: bounds.origin.y as i32,
+                    };
+                    if current != origin {
+                        changes.push(Change {
+                            display: display.clone(),
+                            selector: desired.selector.clone(),
+                            property: "origin".to_string(),
+                            current: format!("({}, {})", current.x, current.y),
+                            desired: format!("({}, {})", origin.x, origin.y),
+                            action: Action::Origin {
+                                display_id: facts.id,
+                                origin,
+                            },
+                        });
+                    }
+                }
+            }
+        }
+
+        // Origins are applied together after everything else
+        changes.sort_by_key(|change| matches!(change.action, Action::Origin { .. }));
+        Ok(changes)
+    }
+}
+
+/// Apply planned changes in order, stopping at the first failure
+///
+/// Origin changes are collected and applied last, snapped into a valid arrangement the same
+/// way `profile apply` does, so that a layout gives the same result through either.
+/// Transient mode change failures are retried per `retry`. On failure the error comes
+/// with the number of leading changes that were applied before it.
+pub fn execute(changes: &[Change], retry: RetryPolicy) -> Result<(), (usize, Error)> {
+    let mut applied = 0;
+    execute_changes(changes, retry, &mut applied).map_err(|err| (applied, err))
+}
+
+fn execute_changes(
+    changes: &[Change],
+    retry: RetryPolicy,
+    applied: &mut usize,
+) -> Result<(), Error> {
+    let mgr = unsafe { MPDisplayMgr::shared().or_else(|| MPDisplayMgr::new()) }
+        .ok_or_else(|| Error::Platform("MonitorPanel manager not available".to_string()))?;
+
+    let mut origins: Vec<(u32, Origin)> = Vec::new();
+
+    for change in changes {
+        match change.action {
+            Action::Mode {
+                display_id,
+                mode_number,
+            } => {
+                let mp_display = unsafe { mgr.display_with_id(display_id) }.ok_or_else(|| {
+                    Error::NotFound(format!("Display {} is no longer connected", change.display))
+                })?;
+                unsafe { set_mode(&mp_display, mode_number, retry) }.map_err(|err| {
+                    Error::Platform(format!(
+                        "Failed to set display {} to mode #{}: {}",
+                        change.display, mode_number, err
+                    ))
+                })?;
+            }
+            Action::Brightness {
+                display_id,
+                brightness,
+            } => unsafe {
+                CoreDisplay_Display_SetUserBrightness(display_id, brightness as f64 / 100.0);
+            },
+            Action::Rotation {
+                display_id,
+                degrees,
+            } => {
+                let mp_display = unsafe { mgr.display_with_id(display_id) }.ok_or_else(|| {
+                    Error::NotFound(format!("Display {} is no longer connected", change.display))
+                })?;
+                unsafe { rotate_display(&mp_display, degrees) }?;
+            }
+            Action::Origin { display_id, origin } => {
+                origins.push((display_id, origin));
+                continue;
+            }
+        }
+        *applied += 1;
+    }
+
+    if !origins.is_empty() {
+        arrange_displays(&origins)?;
+        *applied += origins.len();
+    }
+
+    Ok(())
+}
@@ -0,0 +1,171 @@
+// Layout diagrams
+// Draws the display arrangement as proportional ASCII boxes for terminals and as SVG for
+// sharing, labelled with each display's index, name, resolution and main marker.
+
+use std::fmt::Write;
+
+use crate::layout::{Bounds, Origin};
+
+/// Width of ASCII diagrams in characters
+const ASCII_COLUMNS: i32 = 78;
+
+/// Terminal cells are about twice as tall as they are wide
+const CELL_ASPECT: i32 = 2;
+
+/// A display as drawn in a diagram
+#[derive(Clone, Debug)]
+pub struct DiagramDisplay {
+    /// Position in `list` output, counting from 1
+    pub index: usize,
+    pub name: String,
+    /// Current resolution, e.g. `2560x1440`
+    pub resolution: String,
+    pub bounds: Bounds,
+    pub is_main: bool,
+}
+
+impl DiagramDisplay {
+    fn labels(&self) -> Vec<String> {
+        let mut labels = vec![
+            format!("[{}] {}", self.index, self.name),
+            self.resolution.clone(),
+        ];
+        if self.is_main {
+            labels.push("* main".to_string());
+        }
+        labels
+    }
+}
+
+/// Smallest bounds containing every display
+fn extent(displays: &[DiagramDisplay]) -> Option<Bounds> {
+    let left = displays.iter().map(|d| d.bounds.origin.x).min()?;
+    let top = displays.iter().map(|d| d.bounds.origin.y).min()?;
+    let right = displays.iter().map(|d| d.bounds.right()).max()?;
+    let bottom = displays.iter().map(|d| d.bounds.bottom()).max()?;
+    Some(Bounds {
+        origin: Origin { x: left, y: top },
+        width: right - left,
+        height: bottom - top,
+    })
+}
+
+/// Draw displays as ASCII boxes scaled to fit the terminal
+pub fn render_ascii(displays: &[DiagramDisplay]) -> String {
+    let Some(extent) = extent(displays) else {
+        return String::new();
+    };
+
+    // Points per column and per row, rounded up so the widest display fits
+    let x_scale = (extent.width + ASCII_COLUMNS - 1) / ASCII_COLUMNS;
+    let y_scale = x_scale * CELL_ASPECT;
+    let column = |x: i32| ((x - extent.origin.x) / x_scale) as usize;
+    let row = |y: i32| ((y - extent.origin.y) / y_scale) as usize;
+
+    let columns = column(extent.origin.x + extent.width) + 1;
+    let rows = row(extent.origin.y + extent.height) + 1;
+    let mut grid = vec![vec![' '; columns]; rows];
+
+    for display in displays {
+        let left = column(display.bounds.origin.x);
+        let top = row(display.bounds.origin.y);
+        // Adjacent displays share a border
+        let right = column(display.bounds.right())
+            .max(left + 2)
+            .min(columns - 1);
+        let bottom = row(display.bounds.bottom()).max(top + 2).min(rows - 1);
+
+        grid[top][left..=right].fill('-');
+        grid[bottom][left..=right].fill('-');
+        for line in grid.iter_mut().take(bottom + 1).skip(top) {
+            line[left] = '|';
+            line[right] = '|';
+        }
+        for (x, y) in [(left, top), (right, top), (left, bottom), (right, bottom)] {
+            grid[y][x] = '+';
+        }
+
+        let inner_width = right.saturating_sub(left + 2);
+        for (line, label) in (top + 1..bottom).zip(display.labels()) {
+            for (offset, c) in label.chars().take(inner_width).enumerate() {
+                grid[line][left + 2 + offset] = c;
+            }
+        }
+    }
+
+    let mut out = String::new();
+    for line in grid {
+        let line: String = line.into_iter().collect();
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Draw displays as an SVG document in display points
+pub fn render_svg(displays: &[DiagramDisplay]) -> String {
+    let extent = extent(displays).unwrap_or(Bounds {
+        origin: Origin { x: 0, y: 0 },
+        width: 0,
+        height: 0,
+    });
+    let margin = (extent.width.max(extent.height) / 50).max(1);
+    let font_size = displays
+        .iter()
+        .map(|display| display.bounds.height.min(display.bounds.width / 2))
+        .min()
+        .unwrap_or(0)
+        / 10;
+
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"{} {} {} {}\">",
+        extent.origin.x - margin,
+        extent.origin.y - margin,
+        extent.width + 2 * margin,
+        extent.height + 2 * margin
+    );
+    let _ = writeln!(
+        out,
+        "  <style>text {{ font-family: sans-serif; font-size: {}px; }}</style>",
+        font_size.max(1)
+    );
+
+    for display in displays {
+        let bounds = &display.bounds;
+        let _ = writeln!(
+            out,
+            "  <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\" stroke=\"#333333\" stroke-width=\"{}\"/>",
+            bounds.origin.x,
+            bounds.origin.y,
+            bounds.width,
+            bounds.height,
+            if display.is_main {
+                "#cfe3ff"
+            } else {
+                "#eeeeee"
+            },
+            (margin / 10).max(1)
+        );
+        for (line, label) in display.labels().iter().enumerate() {
+            let _ = writeln!(
+                out,
+                "  <text x=\"{}\" y=\"{}\">{}</text>",
+                bounds.origin.x + font_size,
+                bounds.origin.y + font_size * (2 * line as i32 + 2),
+                escape(label)
+            );
+        }
+    }
+
+    out.push_str("</svg>\n");
+    out
+}
@@ -0,0 +1,102 @@
+// Display kinds
+// MonitorPanel classifies displays as built-in, TV, projector, AirPlay, Sidecar and so
+// on. The classification is reported as tags in listings and can select displays, so
+// policies such as "mirror to projectors" can name a kind of display instead of an id.
+
+use std::fmt;
+
+use clap::ValueEnum;
+use serde::Serialize;
+
+use crate::monitor_panel::MPDisplay;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum DisplayTag {
+    #[serde(rename = "builtin")]
+    #[value(name = "builtin")]
+    BuiltIn,
+    Tv,
+    Projector,
+    #[serde(rename = "airplay")]
+    #[value(name = "airplay")]
+    AirPlay,
+    Sidecar,
+    #[serde(rename = "4k")]
+    #[value(name = "4k")]
+    FourK,
+    /// Apple Pro Display XDR
+    ProDisplay,
+    /// Built-in Retina panel
+    #[serde(rename = "builtin-retina")]
+    #[value(name = "builtin-retina")]
+    BuiltInRetina,
+}
+
+impl DisplayTag {
+    /// Tags in the order they are reported
+    pub const ALL: [DisplayTag; 8] = [
+        DisplayTag::BuiltIn,
+        DisplayTag::BuiltInRetina,
+        DisplayTag::Tv,
+        DisplayTag::Projector,
+        DisplayTag::AirPlay,
+        DisplayTag::Sidecar,
+        DisplayTag::ProDisplay,
+        DisplayTag::FourK,
+    ];
+
+    /// Tags that say what kind of device a display is, most specific first
+    const KINDS: [DisplayTag; 5] = [
+        DisplayTag::Sidecar,
+        DisplayTag::AirPlay,
+        DisplayTag::Projector,
+        DisplayTag::Tv,
+        DisplayTag::BuiltIn,
+    ];
+
+    /// Name used in selectors, `list --type` and JSON
+    pub fn name(self) -> String {
+        self.to_possible_value()
+            .map(|value| value.get_name().to_string())
+            .unwrap_or_default()
+    }
+
+    /// Parse a tag name, ignoring case
+    pub fn from_name(name: &str) -> Option<DisplayTag> {
+        DisplayTag::from_str(name, true).ok()
+    }
+}
+
+impl fmt::Display for DisplayTag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// The kind of device a display is: the most specific kind tag, or `monitor`
+pub fn display_kind(tags: &[DisplayTag]) -> String {
+    DisplayTag::KINDS
+        .iter()
+        .find(|kind| tags.contains(kind))
+        .map_or_else(|| "monitor".to_string(), |kind| kind.name())
+}
+
+/// Tags MonitorPanel reports for a display
+pub unsafe fn display_tags(mp_display: &MPDisplay) -> Vec<DisplayTag> {
+    unsafe {
+        DisplayTag::ALL
+            .into_iter()
+            .filter(|tag| match tag {
+                DisplayTag::BuiltIn => mp_display.is_built_in(),
+                DisplayTag::BuiltInRetina => mp_display.is_built_in_retina(),
+                DisplayTag::Tv => mp_display.is_tv(),
+                DisplayTag::Projector => mp_display.is_projector(),
+                DisplayTag::AirPlay => mp_display.is_airplay_display(),
+                DisplayTag::Sidecar => mp_display.is_sidecar_display(),
+                DisplayTag::ProDisplay => mp_display.is_apple_pro_display(),
+                DisplayTag::FourK => mp_display.is_4k(),
+            })
+            .collect()
+    }
+}
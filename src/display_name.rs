@@ -0,0 +1,105 @@
+// Display names
+// Listings identify displays by the name macOS shows, with EDID vendor and serial.
+// Identical monitors share a name, so each of them gets a numbered suffix such as
+// `DELL U2720Q (2)`, counted in display order across all connected displays.
+
+use std::collections::HashMap;
+
+use core_graphics::display::CGDisplay;
+
+use crate::monitor_panel::MPDisplayMgr;
+use crate::profile::MonitorIdentity;
+
+/// Human-readable identity of a connected display
+#[derive(Clone, Debug)]
+pub struct DisplayLabel {
+    pub id: u32,
+    /// Name made unique among the connected displays
+    pub label: String,
+    /// Three-letter PNP manufacturer id (e.g. `DEL`)
+    pub vendor: String,
+    /// EDID serial number, when the display reports one
+    pub serial: Option<u32>,
+}
+
+/// Give repeated names a numbered suffix, leaving unique names alone
+pub fn dedup_names(names: &[String]) -> Vec<String> {
+    let mut totals: HashMap<&str, usize> = HashMap::new();
+    for name in names {
+        *totals.entry(name.as_str()).or_default() += 1;
+    }
+
+    let mut seen: HashMap<&str, usize> = HashMap::new();
+    names
+        .iter()
+        .map(|name| {
+            if totals[name.as_str()] == 1 {
+                return name.clone();
+            }
+            let count = seen.entry(name.as_str()).or_default();
+            *count += 1;
+            format!("{} ({})", name, count)
+        })
+        .collect()
+}
+
+/// Labels for all active displays, in `list` order
+pub fn connected_labels() -> Vec<DisplayLabel> {
+    let active_displays = CGDisplay::active_displays().unwrap_or_default();
+    let mgr = unsafe { MPDisplayMgr::shared().or_else(|| MPDisplayMgr::new()) };
+
+    let names: Vec<String> = active_displays
+        .iter()
+        .map(|display_id| {
+            let mp_display = mgr
+                .as_ref()
+                .and_then(|mgr| unsafe { mgr.display_with_id(*display_id) });
+            mp_display
+                .as_ref()
+                .and_then(|mp_display| unsafe { mp_display.display_name() })
+                .or_else(|| {
+                    mp_display
+                        .as_ref()
+                        .and_then(|mp_display| unsafe { mp_display.title_name() })
+                })
+                .filter(|name| !name.is_empty())
+                .unwrap_or_else(|| format!("Display {}", display_id))
+        })
+        .collect();
+    let labels = dedup_names(&names);
+
+    active_displays
+        .iter()
+        .zip(names)
+        .zip(labels)
+        .map(|((display_id, name), label)| {
+            let display = CGDisplay::new(*display_id);
+            let identity =
+                MonitorIdentity::from_edid(display.vendor_number(), &name, display.serial_number());
+            DisplayLabel {
+                id: *display_id,
+                label,
+                vendor: identity.vendor,
+                serial: Some(display.serial_number()).filter(|serial| *serial != 0),
+            }
+        })
+        .collect()
+}
+
+/// Label of one display, computed against all connected displays
+pub fn display_label(display_id: u32) -> Option<DisplayLabel> {
+    connected_labels()
+        .into_iter()
+        .find(|label| label.id == display_id)
+}
+
+impl DisplayLabel {
+    /// Print the name, vendor and serial lines of a listing
+    pub fn print(&self, indent: &str) {
+        println!("{}Name: {}", indent, self.label);
+        println!("{}Vendor: {}", indent, self.vendor);
+        if let Some(serial) = self.serial {
+            println!("{}Serial: {}", indent, serial);
+        }
+    }
+}
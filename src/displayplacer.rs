@@ -0,0 +1,477 @@
+// displayplacer command strings
+// Converts between displayplacer arguments such as
+// `"id:… res:1920x1080 hz:60 color_depth:8 scaling:on origin:(0,0) degree:0"` and
+// displayconfig desired states and profiles.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::desired_state::{DesiredDisplay, DesiredState, ModeRequest};
+use crate::error::Error;
+use crate::layout::Origin;
+use crate::mode_descriptor::format_refresh_rate;
+use crate::profile::Profile;
+use crate::rotation::ROTATIONS;
+use crate::selector::DisplaySelector;
+
+/// A full displayplacer invocation: one entry per display (or mirror set)
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DisplayplacerConfig {
+    pub displays: Vec<DisplayplacerDisplay>,
+}
+
+/// Settings for one displayplacer argument
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DisplayplacerDisplay {
+    /// Screen ids; more than one (`id:A+B`) means the displays mirror each other
+    pub ids: Vec<String>,
+    /// Logical resolution (`res:1920x1080`)
+    pub resolution: Option<(i32, i32)>,
+    pub hz: Option<f64>,
+    pub color_depth: Option<i32>,
+    pub enabled: Option<bool>,
+    /// HiDPI scaling (`scaling:on`)
+    pub scaling: Option<bool>,
+    pub origin: Option<Origin>,
+    /// Rotation in degrees
+    pub degree: Option<i32>,
+    /// displayplacer mode number (`mode:N`)
+    pub mode: Option<i32>,
+}
+
+impl FromStr for DisplayplacerConfig {
+    type Err = Error;
+
+    /// Parse a displayplacer command line, with or without the leading `displayplacer`
+    ///
+    /// Each `id:` starts a new display, so the arguments may be quoted individually,
+    /// joined into one string, or split across several lines.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut items: Vec<String> = split_arguments(s)?
+            .iter()
+            .flat_map(|arg| {
+                arg.split_whitespace()
+                    .map(str::to_string)
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        if items.first().is_some_and(|item| item == "displayplacer") {
+            items.remove(0);
+        }
+
+        let mut specs: Vec<Vec<String>> = Vec::new();
+        for item in items {
+            if item.starts_with("id:") {
+                specs.push(vec![item]);
+            } else if let Some(spec) = specs.last_mut() {
+                spec.push(item);
+            } else {
+                return Err(Error::Invalid(format!(
+                    "Expected 'id:' before '{}' in displayplacer arguments",
+                    item
+                )));
+            }
+        }
+
+        if specs.is_empty() {
+            return Err(Error::Invalid(
+                "No displays found in displayplacer arguments".to_string(),
+            ));
+        }
+
+        let displays = specs
+            .iter()
+            .map(|spec| spec.join(" ").parse())
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(DisplayplacerConfig { displays })
+    }
+}
+
+impl FromStr for DisplayplacerDisplay {
+    type Err = Error;
+
+    /// Parse a single `id:… res:… …` argument
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut display = DisplayplacerDisplay::default();
+
+        for item in s.split_whitespace() {
+            let invalid = || Error::Invalid(format!("Invalid displayplacer setting '{}'", item));
+            let (key, value) = item.split_once(':').ok_or_else(invalid)?;
+
+            match key {
+                "id" => {
+                    display.ids = value.split('+').map(str::to_string).collect();
+                    if display.ids.iter().any(|id| id.is_empty()) {
+                        return Err(invalid());
+                    }
+                }
+                "res" => {
+                    let (width, height) = value.split_once('x').ok_or_else(invalid)?;
+                    display.resolution = Some((
+                        width.parse().map_err(|_| invalid())?,
+                        height.parse().map_err(|_| invalid())?,
+                    ));
+                }
+                "hz" => display.hz = Some(value.parse().map_err(|_| invalid())?),
+                "color_depth" => display.color_depth = Some(value.parse().map_err(|_| invalid())?),
+                "enabled" => display.enabled = Some(value.parse().map_err(|_| invalid())?),
+                "scaling" => {
+                    display.scaling = match value {
+                        "on" => Some(true),
+                        "off" => Some(false),
+                        _ => return Err(invalid()),
+                    }
+                }
+                "origin" => {
+                    let (x, y) = value
+                        .strip_prefix('(')
+                        .and_then(|value| value.strip_suffix(')'))
+                        .and_then(|value| value.split_once(','))
+                        .ok_or_else(invalid)?;
+                    display.origin = Some(Origin {
+                        x: x.trim().parse().map_err(|_| invalid())?,
+                        y: y.trim().parse().map_err(|_| invalid())?,
+                    });
+                }
+                "degree" => {
+                    let degree = value.parse().map_err(|_| invalid())?;
+                    if !ROTATIONS.contains(&degree) {
+                        return Err(invalid());
+                    }
+                    display.degree = Some(degree);
+                }
+                "mode" => display.mode = Some(value.parse().map_err(|_| invalid())?),
+                _ => return Err(invalid()),
+            }
+        }
+
+        if display.ids.is_empty() {
+            return Err(Error::Invalid(format!(
+                "Missing 'id:' in displayplacer argument '{}'",
+                s
+            )));
+        }
+        Ok(display)
+    }
+}
+
+impl fmt::Display for DisplayplacerDisplay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "id:{}", self.ids.join("+"))?;
+        if let Some(mode) = self.mode {
+            write!(f, " mode:{}", mode)?;
+        }
+        if let Some((width, height)) = self.resolution {
+            write!(f, " res:{}x{}", width, height)?;
+        }
+        if let Some(hz) = self.hz {
+            write!(f, " hz:{}", format_refresh_rate(hz))?;
+        }
+        if let Some(depth) = self.color_depth {
+            write!(f, " color_depth:{}", depth)?;
+        }
+        if let Some(enabled) = self.enabled {
+            write!(f, " enabled:{}", enabled)?;
+        }
+        if let Some(scaling) = self.scaling {
+            write!(f, " scaling:{}", if scaling { "on" } else { "off" })?;
+        }
+        if let Some(origin) = self.origin {
+            write!(f, " origin:({},{})", origin.x, origin.y)?;
+        }
+        if let Some(degree) = self.degree {
+            write!(f, " degree:{}", degree)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for DisplayplacerConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "displayplacer")?;
+        for display in self.displays.iter() {
+            write!(f, " \"{}\"", display)?;
+        }
+        Ok(())
+    }
+}
+
+impl DisplayplacerConfig {
+    /// Convert to a desired state with one entry per screen id
+    ///
+    /// Disabled displays are left out. Displays in a mirror set receive the same settings,
+    /// but the mirroring itself is not part of the desired state.
+    pub fn to_desired_state(&self) -> Result<DesiredState, Error> {
+        let mut displays = Vec::new();
+
+        for display in self.displays.iter() {
+            if display.enabled == Some(false) {
+                continue;
+            }
+
+            let mode = match (display.resolution, display.mode) {
+                (Some((width, height)), _) => Some(ModeRequest {
+                    width,
+                    height,
+                    refresh_rate: display.hz,
+                    scale: None,
+                    hidpi: display.scaling,
+                    depth: display.color_depth,
+                }),
+                (None, Some(mode)) => {
+                    return Err(Error::Invalid(format!(
+                        "mode:{} for id:{} has no res:; mode numbers are not stable, use res: instead",
+                        mode,
+                        display.ids.join("+")
+                    )));
+                }
+                (None, None) => None,
+            };
+
+            for id in display.ids.iter() {
+                // Contextual ids and UUIDs are supported; serial-number ids (s12345) are not
+                id.parse::<DisplaySelector>().map_err(|_| {
+                    Error::Invalid(format!(
+                        "Unsupported displayplacer id '{}'; use a persistent screen id or contextual id",
+                        id
+                    ))
+                })?;
+
+                displays.push(DesiredDisplay {
+                    selector: id.clone(),
+                    brightness: None,
+                    rotation: display.degree,
+                    mode: mode.clone(),
+                    origin: display.origin,
+                });
+            }
+        }
+
+        Ok(DesiredState { displays })
+    }
+
+    /// Render a profile as displayplacer arguments
+    pub fn from_profile(profile: &Profile) -> Self {
+        let displays = profile
+            .displays
+            .iter()
+            .map(|state| DisplayplacerDisplay {
+                ids: vec![state.uuid.clone()],
+                resolution: state.mode.as_ref().map(|mode| (mode.width, mode.height)),
+                hz: state.mode.as_ref().map(|mode| mode.refresh_rate),
+                color_depth: state.mode.as_ref().and_then(|mode| mode.depth),
+                enabled: Some(true),
+                scaling: state.mode.as_ref().map(|mode| mode.hidpi),
+                origin: state.origin,
+                degree: state.rotation,
+                mode: None,
+            })
+            .collect();
+        DisplayplacerConfig { displays }
+    }
+}
+
+/// Split a command line into arguments, honouring single and double quotes
+fn split_arguments(s: &str) -> Result<Vec<String>, Error> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_arg = false;
+    let mut quote: Option<char> = None;
+    let mut chars = s.chars();
+
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some('"'), '\\') => {
+                if let Some(next) = chars.next() {
+                    current.push(next);
+                }
+            }
+            (Some(_), c) => current.push(c),
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                in_arg = true;
+            }
+            // Line continuations in shell scripts
+            (None, '\\') => match chars.next() {
+                Some('\n') | None => {}
+                Some(next) => {
+                    current.push(next);
+                    in_arg = true;
+                }
+            },
+            (None, c) if c.is_whitespace() => {
+                if in_arg {
+                    args.push(std::mem::take(&mut current));
+                    in_arg = false;
+                }
+            }
+            (None, c) => {
+                current.push(c);
+                in_arg = true;
+            }
+        }
+    }
+
+    if quote.is_some() {
+        return Err(Error::Invalid(
+            "Unterminated quote in displayplacer arguments".to_string(),
+        ));
+    }
+    if in_arg {
+        args.push(current);
+    }
+    Ok(args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DESK: &str = "798186BE-D89C-4988-871A-E111BFFBEA68";
+    const SIDE: &str = "37D8832A-2D66-02CA-B9F7-8F30A301B230";
+
+    fn parse(s: &str) -> DisplayplacerConfig {
+        s.parse().unwrap()
+    }
+
+    /// Parse, format and parse again, which must give the same configuration
+    fn round_trip(s: &str) -> DisplayplacerConfig {
+        let config = parse(s);
+        let formatted = config.to_string();
+        assert_eq!(parse(&formatted), config, "{}", formatted);
+        config
+    }
+
+    #[test]
+    fn full_argument() {
+        let config = round_trip(&format!(
+            "displayplacer \"id:{} res:1920x1080 hz:59.94 color_depth:8 enabled:true \
+             scaling:on origin:(0,0) degree:90\"",
+            DESK
+        ));
+        assert_eq!(
+            config.displays,
+            vec![DisplayplacerDisplay {
+                ids: vec![DESK.to_string()],
+                resolution: Some((1920, 1080)),
+                hz: Some(59.94),
+                color_depth: Some(8),
+                enabled: Some(true),
+                scaling: Some(true),
+                origin: Some(Origin { x: 0, y: 0 }),
+                degree: Some(90),
+                mode: None,
+            }]
+        );
+    }
+
+    #[test]
+    fn quoted_arguments() {
+        let double = round_trip(&format!(
+            "displayplacer \"id:{} res:1920x1080\" \"id:{} res:2560x1440 scaling:off\"",
+            DESK, SIDE
+        ));
+        let single = round_trip(&format!(
+            "displayplacer 'id:{} res:1920x1080' 'id:{} res:2560x1440 scaling:off'",
+            DESK, SIDE
+        ));
+        let unquoted = round_trip(&format!(
+            "id:{} res:1920x1080 id:{} res:2560x1440 scaling:off",
+            DESK, SIDE
+        ));
+        let continued = round_trip(&format!(
+            "displayplacer \\\n  \"id:{} res:1920x1080\" \\\n  \"id:{} res:2560x1440 scaling:off\"",
+            DESK, SIDE
+        ));
+        assert_eq!(double.displays.len(), 2);
+        assert_eq!(double.displays[1].scaling, Some(false));
+        assert_eq!(single, double);
+        assert_eq!(unquoted, double);
+        assert_eq!(continued, double);
+    }
+
+    #[test]
+    fn mirror_set() {
+        let config = round_trip(&format!(
+            "displayplacer \"id:{}+{} res:1920x1080 origin:(0,0)\"",
+            DESK, SIDE
+        ));
+        assert_eq!(config.displays.len(), 1);
+        assert_eq!(
+            config.displays[0].ids,
+            vec![DESK.to_string(), SIDE.to_string()]
+        );
+        assert!(
+            config
+                .to_string()
+                .contains(&format!("id:{}+{}", DESK, SIDE))
+        );
+    }
+
+    #[test]
+    fn negative_origin() {
+        let config = round_trip(&format!(
+            "displayplacer \"id:{} res:1920x1080 origin:(-1920,0)\" \"id:{} origin:(0,-1080)\"",
+            DESK, SIDE
+        ));
+        assert_eq!(config.displays[0].origin, Some(Origin { x: -1920, y: 0 }));
+        assert_eq!(config.displays[1].origin, Some(Origin { x: 0, y: -1080 }));
+        assert!(config.to_string().contains("origin:(-1920,0)"));
+    }
+
+    #[test]
+    fn mode_number() {
+        let config = round_trip(&format!("\"id:{} mode:12 degree:180\"", DESK));
+        assert_eq!(config.displays[0].mode, Some(12));
+        assert!(config.to_desired_state().is_err());
+    }
+
+    #[test]
+    fn malformed_input_is_rejected() {
+        let malformed = [
+            String::new(),
+            "displayplacer".to_string(),
+            "res:1920x1080".to_string(),
+            format!("\"id:{} res:1920x1080", DESK),
+            format!("id:{} res:1920", DESK),
+            format!("id:{} res:widexhigh", DESK),
+            format!("id:{} hz:fast", DESK),
+            format!("id:{} scaling:yes", DESK),
+            format!("id:{} origin:0,0", DESK),
+            format!("id:{} origin:(0)", DESK),
+            format!("id:{} degree:45", DESK),
+            format!("id:{} resolution:1920x1080", DESK),
+            format!("id:{} res", DESK),
+            format!("id:{}+", DESK),
+            "id:".to_string(),
+        ];
+        for input in malformed {
+            assert!(
+                input.parse::<DisplayplacerConfig>().is_err(),
+                "accepted '{}'",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn desired_state_keeps_settings_per_screen() {
+        let config = parse(&format!(
+            "displayplacer \"id:{}+{} res:1920x1080 hz:60 scaling:on origin:(-1920,0) degree:0\" \
+             \"id:{} enabled:false\"",
+            DESK, SIDE, SIDE
+        ));
+        let state = config.to_desired_state().unwrap();
+        let selectors: Vec<_> = state
+            .displays
+            .iter()
+            .map(|display| display.selector.as_str())
+            .collect();
+        assert_eq!(selectors, vec![DESK, SIDE]);
+        assert!(state.displays.iter().all(|display| {
+            display.origin == Some(Origin { x: -1920, y: 0 })
+                && display.mode.as_ref().map(|mode| (mode.width, mode.height)) == Some((1920, 1080))
+        }));
+    }
+}
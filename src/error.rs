@@ -0,0 +1,40 @@
+// Error type for operations that load, store or apply display configurations
+
+use std::fmt;
+
+#[derive(Debug)]
+pub enum Error {
+    /// Reading or writing a file failed
+    Io(std::io::Error),
+    /// A stored configuration could not be parsed or serialized
+    Format(String),
+    /// A profile, display or mode does not exist
+    NotFound(String),
+    /// A user-supplied value was rejected
+    Invalid(String),
+    /// MonitorPanel or Core Graphics reported a failure
+    Platform(String),
+    /// Another displayconfig process is reconfiguring the displays
+    Busy(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(err) => write!(f, "{}", err),
+            Error::Format(msg)
+            | Error::NotFound(msg)
+            | Error::Invalid(msg)
+            | Error::Platform(msg)
+            | Error::Busy(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<std::io::Error> for Error {
+    fn from(err: std::io::Error) -> Self {
+        Error::Io(err)
+    }
+}
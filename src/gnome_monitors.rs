@@ -0,0 +1,696 @@
+// GNOME monitors.xml
+// Reads and writes the layouts GNOME keeps in `~/.config/monitors.xml`. Each
+// configuration there describes one set of monitors, identified by connector and EDID
+// vendor, product and serial; profiles are matched to it by EDID identity. Written
+// configurations use GNOME's logical layout mode, where positions are in logical pixels
+// like macOS points.
+
+use std::fmt::Write;
+
+use crate::error::Error;
+use crate::layout::Origin;
+use crate::linux_outputs::OutputConfig;
+use crate::profile::{DisplayState, MonitorIdentity, Profile};
+
+/// A monitor inside a logical monitor
+#[derive(Clone, Debug, PartialEq)]
+pub struct GnomeMonitor {
+    pub connector: String,
+    pub identity: MonitorIdentity,
+    /// Mode size in physical pixels and refresh rate
+    pub mode: Option<(i32, i32, f64)>,
+}
+
+/// A region of the layout shown by one monitor, or several when mirrored
+#[derive(Clone, Debug, PartialEq)]
+pub struct GnomeLogicalMonitor {
+    pub origin: Origin,
+    pub scale: f32,
+    pub primary: bool,
+    /// Clockwise rotation in degrees
+    pub rotation: i32,
+    pub monitors: Vec<GnomeMonitor>,
+}
+
+/// One `<configuration>` element
+#[derive(Clone, Debug, PartialEq)]
+pub struct GnomeConfiguration {
+    pub logical_monitors: Vec<GnomeLogicalMonitor>,
+    /// Original XML text, kept so configurations can be written back unchanged
+    pub source: Option<String>,
+}
+
+impl GnomeConfiguration {
+    /// EDID identities of every monitor in this configuration, sorted
+    pub fn identities(&self) -> Vec<&MonitorIdentity> {
+        let mut identities: Vec<&MonitorIdentity> = self
+            .logical_monitors
+            .iter()
+            .flat_map(|logical| logical.monitors.iter().map(|monitor| &monitor.identity))
+            .collect();
+        identities.sort_by(|a, b| {
+            (&a.vendor, &a.product, &a.serial).cmp(&(&b.vendor, &b.product, &b.serial))
+        });
+        identities
+    }
+
+    /// Build a configuration from a profile
+    ///
+    /// Every display needs an EDID identity and a connector name (the profile's Linux
+    /// output name, or an entry in `connectors` keyed by UUID).
+    pub fn from_profile(profile: &Profile, connectors: &[(String, String)]) -> Result<Self, Error> {
+        let mut logical_monitors = Vec::new();
+
+        for state in profile.displays.iter() {
+            let identity = state.monitor.clone().ok_or_else(|| {
+                Error::NotFound(format!(
+                    "Display {} has no EDID identity; save the profile again on macOS",
+                    state.uuid
+                ))
+            })?;
+            let connector = connectors
+                .iter()
+                .find(|(uuid, _)| uuid.eq_ignore_ascii_case(&state.uuid))
+                .map(|(_, connector)| connector.clone())
+                .or_else(|| state.output.clone())
+                .ok_or_else(|| {
+                    Error::NotFound(format!(
+                        "Display {} has no connector name; pass --output-name {}=<connector>",
+                        state.uuid, state.uuid
+                    ))
+                })?;
+
+            let origin = state.origin.unwrap_or(Origin { x: 0, y: 0 });
+            logical_monitors.push(GnomeLogicalMonitor {
+                origin,
+                scale: state.mode.as_ref().map_or(1.0, |mode| mode.scale),
+                primary: origin == Origin { x: 0, y: 0 },
+                rotation: state.rotation.unwrap_or(0),
+                monitors: vec![GnomeMonitor {
+                    connector,
+                    identity,
+                    mode: state
+                        .mode
+                        .as_ref()
+                        .map(|mode| (mode.pixels_wide, mode.pixels_high, mode.refresh_rate)),
+                }],
+            });
+        }
+
+        Ok(GnomeConfiguration {
+            logical_monitors,
+            source: None,
+        })
+    }
+
+    /// Update a profile from this configuration
+    ///
+    /// Monitors are matched to displays by EDID vendor, product and serial, then by vendor
+    /// and product when that is unambiguous, then by connector name. Unmatched monitors are
+    /// added with the connector standing in for the UUID.
+    pub fn merge_into(&self, profile: &mut Profile) {
+        for logical in self.logical_monitors.iter() {
+            for monitor in logical.monitors.iter() {
+                let output = OutputConfig {
+                    name: monitor.connector.clone(),
+                    enabled: true,
+                    mode: monitor.mode.map(|(width, height, _)| (width, height)),
+                    refresh_rate: monitor.mode.map(|(_, _, rate)| rate),
+                    position: Some(logical.origin),
+                    scale: Some(logical.scale),
+                    transform: Some(logical.rotation),
+                };
+
+                let index = find_display(&profile.displays, monitor);
+                let state = match index {
+                    Some(index) => &mut profile.displays[index],
+                    None => {
+                        profile.displays.push(DisplayState {
+                            uuid: monitor.connector.clone(),
+                            name: Some(monitor.identity.product.clone()),
+                            output: None,
+                            monitor: None,
+                            brightness: None,
+                            rotation: None,
+                            origin: None,
+                            mode: None,
+                            preset: None,
+                            hdr: None,
+                        });
+                        profile.displays.last_mut().expect("display was just added")
+                    }
+                };
+                output.update(state);
+                state.monitor = Some(monitor.identity.clone());
+            }
+        }
+    }
+}
+
+fn find_display(displays: &[DisplayState], monitor: &GnomeMonitor) -> Option<usize> {
+    let exact = displays
+        .iter()
+        .position(|state| state.monitor.as_ref() == Some(&monitor.identity));
+    if exact.is_some() {
+        return exact;
+    }
+
+    let same_model: Vec<usize> = displays
+        .iter()
+        .enumerate()
+        .filter(|(_, state)| {
+            state.monitor.as_ref().is_some_and(|identity| {
+                identity.vendor == monitor.identity.vendor
+                    && identity.product == monitor.identity.product
+            })
+        })
+        .map(|(index, _)| index)
+        .collect();
+    if same_model.len() == 1 {
+        return same_model.first().copied();
+    }
+
+    displays
+        .iter()
+        .position(|state| state.output.as_deref() == Some(monitor.connector.as_str()))
+}
+
+/// GNOME rotation names, where `left` is a quarter turn counterclockwise
+fn rotation_name(degrees: i32) -> &'static str {
+    match degrees {
+        90 => "right",
+        180 => "upside_down",
+        270 => "left",
+        _ => "normal",
+    }
+}
+
+fn rotation_degrees(name: &str) -> Result<i32, Error> {
+    match name {
+        "normal" => Ok(0),
+        "right" => Ok(90),
+        "upside_down" => Ok(180),
+        "left" => Ok(270),
+        _ => Err(Error::Format(format!(
+            "Unknown rotation '{}' in monitors.xml",
+            name
+        ))),
+    }
+}
+
+fn child<'a, 'input>(
+    node: roxmltree::Node<'a, 'input>,
+    name: &str,
+) -> Option<roxmltree::Node<'a, 'input>> {
+    node.children().find(|child| child.has_tag_name(name))
+}
+
+fn child_text<'a>(node: roxmltree::Node<'a, '_>, name: &str) -> Option<&'a str> {
+    child(node, name)
+        .and_then(|child| child.text())
+        .map(str::trim)
+}
+
+fn parse_number<T: std::str::FromStr>(node: roxmltree::Node, name: &str) -> Result<T, Error> {
+    child_text(node, name)
+        .and_then(|text| text.parse().ok())
+        .ok_or_else(|| Error::Format(format!("Missing or invalid <{}> in monitors.xml", name)))
+}
+
+fn parse_monitor(node: roxmltree::Node) -> Result<GnomeMonitor, Error> {
+    let spec = child(node, "monitorspec")
+        .ok_or_else(|| Error::Format("Missing <monitorspec> in monitors.xml".to_string()))?;
+    let text = |name: &str| child_text(spec, name).unwrap_or_default().to_string();
+
+    let mode = match child(node, "mode") {
+        Some(mode) => Some((
+            parse_number(mode, "width")?,
+            parse_number(mode, "height")?,
+            parse_number(mode, "rate")?,
+        )),
+        None => None,
+    };
+
+    Ok(GnomeMonitor {
+        connector: text("connector"),
+        identity: MonitorIdentity {
+            vendor: text("vendor"),
+            product: text("product"),
+            serial: text("serial"),
+        },
+        mode,
+    })
+}
+
+fn parse_logical_monitor(node: roxmltree::Node) -> Result<GnomeLogicalMonitor, Error> {
+    let rotation =
+        match child(node, "transform").and_then(|transform| child_text(transform, "rotation")) {
+            Some(name) => rotation_degrees(name)?,
+            None => 0,
+        };
+
+    Ok(GnomeLogicalMonitor {
+        origin: Origin {
+            x: parse_number(node, "x")?,
+            y: parse_number(node, "y")?,
+        },
+        scale: child_text(node, "scale")
+            .map(|scale| scale.parse())
+            .transpose()
+            .map_err(|_| Error::Format("Invalid <scale> in monitors.xml".to_string()))?
+            .unwrap_or(1.0),
+        primary: child_text(node, "primary") == Some("yes"),
+        rotation,
+        monitors: node
+            .children()
+            .filter(|child| child.has_tag_name("monitor"))
+            .map(parse_monitor)
+            .collect::<Result<_, _>>()?,
+    })
+}
+
+/// Parse every configuration in a monitors.xml document
+pub fn parse_monitors_xml(text: &str) -> Result<Vec<GnomeConfiguration>, Error> {
+    let document = roxmltree::Document::parse(text)
+        .map_err(|err| Error::Format(format!("Failed to parse monitors.xml: {}", err)))?;
+    let root = document.root_element();
+    if !root.has_tag_name("monitors") {
+        return Err(Error::Format(
+            "monitors.xml does not start with <monitors>".to_string(),
+        ));
+    }
+
+    root.children()
+        .filter(|node| node.has_tag_name("configuration"))
+        .map(|node| {
+            Ok(GnomeConfiguration {
+                logical_monitors: node
+                    .children()
+                    .filter(|child| child.has_tag_name("logicalmonitor"))
+                    .map(parse_logical_monitor)
+                    .collect::<Result<_, _>>()?,
+                source: Some(text[node.range()].to_string()),
+            })
+        })
+        .collect()
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn render_configuration(out: &mut String, configuration: &GnomeConfiguration) {
+    if let Some(source) = &configuration.source {
+        let _ = writeln!(out, "  {}", source);
+        return;
+    }
+
+    out.push_str("  <configuration>\n");
+    out.push_str("    <layoutmode>logical</layoutmode>\n");
+    for logical in configuration.logical_monitors.iter() {
+        out.push_str("    <logicalmonitor>\n");
+        let _ = writeln!(out, "      <x>{}</x>", logical.origin.x);
+        let _ = writeln!(out, "      <y>{}</y>", logical.origin.y);
+        let _ = writeln!(out, "      <scale>{}</scale>", logical.scale);
+        if logical.primary {
+            out.push_str("      <primary>yes</primary>\n");
+        }
+        if logical.rotation != 0 {
+            out.push_str("      <transform>\n");
+            let _ = writeln!(
+                out,
+                "        <rotation>{}</rotation>",
+                rotation_name(logical.rotation)
+            );
+            out.push_str("        <flipped>no</flipped>\n");
+            out.push_str("      </transform>\n");
+        }
+        for monitor in logical.monitors.iter() {
+            out.push_str("      <monitor>\n");
+            out.push_str("        <monitorspec>\n");
+            let _ = writeln!(
+                out,
+                "          <connector>{}</connector>",
+                escape(&monitor.connector)
+            );
+            let _ = writeln!(
+                out,
+                "          <vendor>{}</vendor>",
+                escape(&monitor.identity.vendor)
+            );
+            let _ = writeln!(
+                out,
+                "          <product>{}</product>",
+                escape(&monitor.identity.product)
+            );
+            let _ = writeln!(
+                out,
+                "          <serial>{}</serial>",
+                escape(&monitor.identity.serial)
+            );
+            out.push_str("        </monitorspec>\n");
+            if let Some((width, height, rate)) = monitor.mode {
+                out.push_str("        <mode>\n");
+                let _ = writeln!(out, "          <width>{}</width>", width);
+                let _ = writeln!(out, "          <height>{}</height>", height);
+                // mutter compares rates closely, so keep every digit rather than rounding
+                let _ = writeln!(out, "          <rate>{}</rate>", rate);
+                out.push_str("        </mode>\n");
+            }
+            out.push_str("      </monitor>\n");
+        }
+        out.push_str("    </logicalmonitor>\n");
+    }
+    out.push_str("  </configuration>\n");
+}
+
+/// Render a monitors.xml document
+pub fn render_monitors_xml(configurations: &[GnomeConfiguration]) -> String {
+    let mut out = String::from("<monitors version=\"2\">\n");
+    for configuration in configurations {
+        render_configuration(&mut out, configuration);
+    }
+    out.push_str("</monitors>\n");
+    out
+}
+
+/// Replace the configuration for the same set of monitors in `existing`, or append it
+pub fn upsert_configuration(
+    existing: &mut Vec<GnomeConfiguration>,
+    configuration: GnomeConfiguration,
+) {
+    let identities = configuration.identities();
+    match existing
+        .iter()
+        .position(|other| other.identities() == identities)
+    {
+        Some(index) => existing[index] = configuration,
+        None => existing.push(configuration),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mode_descriptor::ModeDescriptor;
+
+    /// A docked laptop with a portrait monitor on its right, followed by a configuration
+    /// for a projector that exports should leave untouched
+    const MONITORS_XML: &str = r#"<monitors version="2">
+  <configuration>
+    <layoutmode>logical</layoutmode>
+    <logicalmonitor>
+      <x>0</x>
+      <y>0</y>
+      <scale>2</scale>
+      <primary>yes</primary>
+      <monitor>
+        <monitorspec>
+          <connector>eDP-1</connector>
+          <vendor>APP</vendor>
+          <product>Color LCD</product>
+          <serial>0x00000000</serial>
+        </monitorspec>
+        <mode>
+          <width>2880</width>
+          <height>1800</height>
+          <rate>59.999</rate>
+        </mode>
+      </monitor>
+    </logicalmonitor>
+    <logicalmonitor>
+      <x>1440</x>
+      <y>0</y>
+      <scale>1</scale>
+      <transform>
+        <rotation>right</rotation>
+        <flipped>no</flipped>
+      </transform>
+      <monitor>
+        <monitorspec>
+          <connector>DP-1</connector>
+          <vendor>DEL</vendor>
+          <product>DELL U2720Q</product>
+          <serial>ABC123</serial>
+        </monitorspec>
+        <mode>
+          <width>3840</width>
+          <height>2160</height>
+          <rate>60</rate>
+        </mode>
+      </monitor>
+    </logicalmonitor>
+  </configuration>
+  <configuration>
+    <logicalmonitor>
+      <x>0</x>
+      <y>0</y>
+      <primary>yes</primary>
+      <monitor>
+        <monitorspec>
+          <connector>HDMI-1</connector>
+          <vendor>EPS</vendor>
+          <product>EPSON PJ</product>
+          <serial>0x01010101</serial>
+        </monitorspec>
+      </monitor>
+    </logicalmonitor>
+  </configuration>
+</monitors>
+"#;
+
+    fn identity(vendor: &str, product: &str, serial: &str) -> MonitorIdentity {
+        MonitorIdentity {
+            vendor: vendor.to_string(),
+            product: product.to_string(),
+            serial: serial.to_string(),
+        }
+    }
+
+    fn state(uuid: &str, monitor: Option<MonitorIdentity>) -> DisplayState {
+        DisplayState {
+            uuid: uuid.to_string(),
+            name: None,
+            output: None,
+            monitor,
+            brightness: None,
+            rotation: None,
+            origin: None,
+            mode: None,
+            preset: None,
+            hdr: None,
+        }
+    }
+
+    /// A mode at 60Hz with `scale` times fewer points than pixels
+    fn mode(pixels_wide: i32, pixels_high: i32, scale: f32) -> ModeDescriptor {
+        ModeDescriptor {
+            width: (pixels_wide as f32 / scale) as i32,
+            height: (pixels_high as f32 / scale) as i32,
+            pixels_wide,
+            pixels_high,
+            scale,
+            refresh_rate: 60.0,
+            hidpi: scale > 1.0,
+            depth: None,
+            hdr: false,
+        }
+    }
+
+    fn profile(displays: Vec<DisplayState>) -> Profile {
+        Profile {
+            name: "desk".to_string(),
+            displays,
+        }
+    }
+
+    #[test]
+    fn parse() {
+        let configurations = parse_monitors_xml(MONITORS_XML).unwrap();
+        assert_eq!(configurations.len(), 2);
+
+        let docked = &configurations[0].logical_monitors;
+        assert_eq!(docked.len(), 2);
+        assert_eq!(docked[0].scale, 2.0);
+        assert!(docked[0].primary);
+        assert_eq!(docked[0].monitors[0].mode, Some((2880, 1800, 59.999)));
+        assert_eq!(docked[1].origin, Origin { x: 1440, y: 0 });
+        assert_eq!(docked[1].rotation, 90);
+        assert!(!docked[1].primary);
+        assert_eq!(
+            docked[1].monitors[0].identity,
+            identity("DEL", "DELL U2720Q", "ABC123")
+        );
+
+        let projector = &configurations[1].logical_monitors[0];
+        assert_eq!(projector.scale, 1.0);
+        assert_eq!(projector.monitors[0].mode, None);
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert!(matches!(
+            parse_monitors_xml("<displays/>"),
+            Err(Error::Format(_))
+        ));
+        assert!(matches!(
+            parse_monitors_xml("<monitors>"),
+            Err(Error::Format(_))
+        ));
+        let bad_rotation = MONITORS_XML.replace(
+            "<rotation>right</rotation>",
+            "<rotation>sideways</rotation>",
+        );
+        match parse_monitors_xml(&bad_rotation) {
+            Err(Error::Format(message)) => assert!(message.contains("sideways"), "{}", message),
+            other => panic!("expected a format error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn rotations() {
+        for degrees in [0, 90, 180, 270] {
+            assert_eq!(rotation_degrees(rotation_name(degrees)).unwrap(), degrees);
+        }
+        assert_eq!(rotation_name(90), "right");
+        assert_eq!(rotation_name(270), "left");
+    }
+
+    #[test]
+    fn unchanged_configurations_are_written_back_verbatim() {
+        let configurations = parse_monitors_xml(MONITORS_XML).unwrap();
+        assert_eq!(render_monitors_xml(&configurations), MONITORS_XML);
+    }
+
+    #[test]
+    fn rendered_configurations_parse_back() {
+        let mut configurations = parse_monitors_xml(MONITORS_XML).unwrap();
+        for configuration in configurations.iter_mut() {
+            configuration.source = None;
+        }
+        let rendered = render_monitors_xml(&configurations);
+        assert!(rendered.contains("<layoutmode>logical</layoutmode>"));
+
+        let mut parsed = parse_monitors_xml(&rendered).unwrap();
+        for configuration in parsed.iter_mut() {
+            configuration.source = None;
+        }
+        assert_eq!(parsed, configurations);
+    }
+
+    #[test]
+    fn from_profile() {
+        let mut laptop = state("LAPTOP", Some(identity("APP", "Color LCD", "0x00000000")));
+        laptop.output = Some("eDP-1".to_string());
+        laptop.mode = Some(mode(2880, 1800, 2.0));
+        laptop.origin = Some(Origin { x: 0, y: 0 });
+        let mut dell = state("DELL", Some(identity("DEL", "DELL U2720Q", "ABC123")));
+        dell.rotation = Some(90);
+        dell.origin = Some(Origin { x: 1440, y: 0 });
+        let desk = profile(vec![laptop, dell]);
+
+        let configuration =
+            GnomeConfiguration::from_profile(&desk, &[("dell".to_string(), "DP-1".to_string())])
+                .unwrap();
+        let logical = &configuration.logical_monitors;
+        assert!(logical[0].primary);
+        assert_eq!(logical[0].scale, 2.0);
+        assert_eq!(logical[0].monitors[0].connector, "eDP-1");
+        assert_eq!(logical[0].monitors[0].mode, Some((2880, 1800, 60.0)));
+        assert!(!logical[1].primary);
+        assert_eq!(logical[1].rotation, 90);
+        assert_eq!(logical[1].monitors[0].connector, "DP-1");
+
+        // Without a connector mapping the Dell cannot be written
+        assert!(matches!(
+            GnomeConfiguration::from_profile(&desk, &[]),
+            Err(Error::NotFound(_))
+        ));
+        // Nor can a display saved before EDID identities were recorded
+        assert!(matches!(
+            GnomeConfiguration::from_profile(&profile(vec![state("OLD", None)]), &[]),
+            Err(Error::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn upsert_replaces_the_same_monitors_and_appends_others() {
+        let mut configurations = parse_monitors_xml(MONITORS_XML).unwrap();
+        let mut docked = configurations[0].clone();
+        docked.source = None;
+        docked.logical_monitors[1].origin = Origin { x: -1080, y: 0 };
+        // Monitor order does not matter
+        docked.logical_monitors.reverse();
+
+        upsert_configuration(&mut configurations, docked.clone());
+        assert_eq!(configurations.len(), 2);
+        assert_eq!(configurations[0], docked);
+        assert!(configurations[1].source.is_some());
+
+        let mut travel = docked.clone();
+        travel.logical_monitors.truncate(1);
+        upsert_configuration(&mut configurations, travel.clone());
+        assert_eq!(configurations.len(), 3);
+        assert_eq!(configurations[2], travel);
+    }
+
+    #[test]
+    fn find_display_by_identity_model_and_connector() {
+        let configurations = parse_monitors_xml(MONITORS_XML).unwrap();
+        let laptop = &configurations[0].logical_monitors[0].monitors[0];
+        let dell = &configurations[0].logical_monitors[1].monitors[0];
+
+        let exact = vec![
+            state("OTHER", Some(identity("DEL", "DELL U2720Q", "XYZ789"))),
+            state("DELL", Some(identity("DEL", "DELL U2720Q", "ABC123"))),
+        ];
+        assert_eq!(find_display(&exact, dell), Some(1));
+
+        // A different serial still matches when only one display is that model
+        let serial_differs = vec![
+            state("LAPTOP", Some(identity("APP", "Color LCD", "0x00000000"))),
+            state("DELL", Some(identity("DEL", "DELL U2720Q", "0x0001e240"))),
+        ];
+        assert_eq!(find_display(&serial_differs, dell), Some(1));
+
+        // Two of the same model with other serials are ambiguous
+        let ambiguous = vec![
+            state("LEFT", Some(identity("DEL", "DELL U2720Q", "1"))),
+            state("RIGHT", Some(identity("DEL", "DELL U2720Q", "2"))),
+        ];
+        assert_eq!(find_display(&ambiguous, dell), None);
+
+        let mut by_connector = state("LAPTOP", None);
+        by_connector.output = Some("eDP-1".to_string());
+        assert_eq!(find_display(&[by_connector], laptop), Some(0));
+    }
+
+    #[test]
+    fn merge_into_updates_and_adds_displays() {
+        let configurations = parse_monitors_xml(MONITORS_XML).unwrap();
+        let mut desk = profile(vec![state(
+            "DELL",
+            Some(identity("DEL", "DELL U2720Q", "0x0001e240")),
+        )]);
+        configurations[0].merge_into(&mut desk);
+
+        assert_eq!(desk.displays.len(), 2);
+        let dell = &desk.displays[0];
+        assert_eq!(dell.uuid, "DELL");
+        assert_eq!(dell.output.as_deref(), Some("DP-1"));
+        assert_eq!(dell.origin, Some(Origin { x: 1440, y: 0 }));
+        assert_eq!(dell.rotation, Some(90));
+        // The identity GNOME recorded replaces the one saved on macOS
+        assert_eq!(dell.monitor, Some(identity("DEL", "DELL U2720Q", "ABC123")));
+
+        let laptop = &desk.displays[1];
+        assert_eq!(laptop.uuid, "eDP-1");
+        assert_eq!(laptop.name.as_deref(), Some("Color LCD"));
+        let laptop_mode = laptop.mode.as_ref().unwrap();
+        assert_eq!((laptop_mode.width, laptop_mode.height), (1440, 900));
+        assert_eq!(laptop_mode.refresh_rate, 59.999);
+    }
+}
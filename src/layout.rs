@@ -0,0 +1,613 @@
+// Display layout geometry
+// Origins and bounds of displays in the global display coordinate space, where the main
+// display's top-left corner is at (0, 0), and a solver that checks and tidies arrangements
+// the way the Displays settings do. Nothing here talks to display hardware.
+
+use std::fmt;
+use std::str::FromStr;
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+
+/// Top-left corner of a display in the global display coordinate space
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Origin {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl FromStr for Origin {
+    type Err = Error;
+
+    /// Parse `X,Y`, optionally in parentheses (`(-1920,0)`)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::Invalid(format!("Invalid origin '{}', expected X,Y", s));
+        let inner = s
+            .trim()
+            .strip_prefix('(')
+            .and_then(|rest| rest.strip_suffix(')'))
+            .unwrap_or(s.trim());
+        let (x, y) = inner.split_once(',').ok_or_else(invalid)?;
+        Ok(Origin {
+            x: x.trim().parse().map_err(|_| invalid())?,
+            y: y.trim().parse().map_err(|_| invalid())?,
+        })
+    }
+}
+
+impl fmt::Display for Origin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+/// Position and size of a display in points
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Bounds {
+    pub origin: Origin,
+    pub width: i32,
+    pub height: i32,
+}
+
+impl Bounds {
+    pub fn right(&self) -> i32 {
+        self.origin.x + self.width
+    }
+
+    pub fn bottom(&self) -> i32 {
+        self.origin.y + self.height
+    }
+
+    /// Whether the two bounds cover a common area
+    pub fn overlaps(&self, other: &Bounds) -> bool {
+        self.origin.x < other.right()
+            && other.origin.x < self.right()
+            && self.origin.y < other.bottom()
+            && other.origin.y < self.bottom()
+    }
+
+    /// Whether the two bounds share part of an edge, as displays must to move the pointer
+    /// between them
+    pub fn touches(&self, other: &Bounds) -> bool {
+        let share_columns = self.origin.x < other.right() && other.origin.x < self.right();
+        let share_rows = self.origin.y < other.bottom() && other.origin.y < self.bottom();
+        ((self.right() == other.origin.x || other.right() == self.origin.x) && share_rows)
+            || ((self.bottom() == other.origin.y || other.bottom() == self.origin.y)
+                && share_columns)
+    }
+
+    fn moved_to(&self, origin: Origin) -> Bounds {
+        Bounds { origin, ..*self }
+    }
+}
+
+impl fmt::Display for Bounds {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}x{} at {}", self.width, self.height, self.origin)
+    }
+}
+
+/// Side of another display to place a display on
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Placement {
+    RightOf,
+    LeftOf,
+    Above,
+    Below,
+}
+
+/// Edge shared with the other display when placing relative to it
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Alignment {
+    Top,
+    Bottom,
+    Left,
+    Right,
+    Center,
+}
+
+/// Origin that puts a `width` x `height` display next to `anchor`
+///
+/// Displays placed left or right of the anchor align `top`, `bottom` or `center`; displays
+/// placed above or below align `left`, `right` or `center`. Without an alignment they
+/// line up with the anchor's top or left edge.
+pub fn place_relative(
+    width: i32,
+    height: i32,
+    anchor: &Bounds,
+    placement: Placement,
+    alignment: Option<Alignment>,
+) -> Result<Origin, Error> {
+    let horizontal = matches!(placement, Placement::RightOf | Placement::LeftOf);
+    let alignment = alignment.unwrap_or(if horizontal {
+        Alignment::Top
+    } else {
+        Alignment::Left
+    });
+
+    let cross = match (horizontal, alignment) {
+        (true, Alignment::Top) => anchor.origin.y,
+        (true, Alignment::Bottom) => anchor.origin.y + anchor.height - height,
+        (true, Alignment::Center) => anchor.origin.y + (anchor.height - height) / 2,
+        (false, Alignment::Left) => anchor.origin.x,
+        (false, Alignment::Right) => anchor.origin.x + anchor.width - width,
+        (false, Alignment::Center) => anchor.origin.x + (anchor.width - width) / 2,
+        _ => {
+            return Err(Error::Invalid(format!(
+                "Alignment '{}' does not apply to displays placed {}",
+                alignment
+                    .to_possible_value()
+                    .map(|value| value.get_name().to_string())
+                    .unwrap_or_default(),
+                if horizontal {
+                    "left or right of another (use top, bottom or center)"
+                } else {
+                    "above or below another (use left, right or center)"
+                }
+            )));
+        }
+    };
+
+    Ok(match placement {
+        Placement::RightOf => Origin {
+            x: anchor.origin.x + anchor.width,
+            y: cross,
+        },
+        Placement::LeftOf => Origin {
+            x: anchor.origin.x - width,
+            y: cross,
+        },
+        Placement::Above => Origin {
+            x: cross,
+            y: anchor.origin.y - height,
+        },
+        Placement::Below => Origin {
+            x: cross,
+            y: anchor.origin.y + anchor.height,
+        },
+    })
+}
+
+/// A display taking part in a layout
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LayoutDisplay {
+    pub id: u32,
+    pub bounds: Bounds,
+    pub is_main: bool,
+}
+
+/// A problem with an arrangement
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LayoutIssue {
+    /// Two displays cover a common area
+    Overlap(u32, u32),
+    /// A display shares no edge with the displays connected to the main display
+    Detached(u32),
+}
+
+impl fmt::Display for LayoutIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LayoutIssue::Overlap(a, b) => write!(f, "Displays {} and {} overlap", a, b),
+            LayoutIssue::Detached(id) => write!(
+                f,
+                "Display {} does not share an edge with the other displays",
+                id
+            ),
+        }
+    }
+}
+
+/// Displays with requested positions, checked and tidied as a whole
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Layout {
+    displays: Vec<LayoutDisplay>,
+}
+
+impl Layout {
+    /// Exactly one display must be main, ids must be unique and sizes positive
+    pub fn new(displays: Vec<LayoutDisplay>) -> Result<Self, Error> {
+        let mains = displays.iter().filter(|display| display.is_main).count();
+        if mains != 1 {
+            return Err(Error::Invalid(format!(
+                "A layout needs exactly one main display, found {}",
+                mains
+            )));
+        }
+        for (index, display) in displays.iter().enumerate() {
+            if display.bounds.width <= 0 || display.bounds.height <= 0 {
+                return Err(Error::Invalid(format!(
+                    "Display {} has an empty size {}x{}",
+                    display.id, display.bounds.width, display.bounds.height
+                )));
+            }
+            if displays[..index].iter().any(|other| other.id == display.id) {
+                return Err(Error::Invalid(format!(
+                    "Display {} appears twice in the layout",
+                    display.id
+                )));
+            }
+        }
+        Ok(Layout { displays })
+    }
+
+    pub fn origin_of(&self, id: u32) -> Option<Origin> {
+        self.displays
+            .iter()
+            .find(|display| display.id == id)
+            .map(|display| display.bounds.origin)
+    }
+
+    fn main_index(&self) -> usize {
+        self.displays
+            .iter()
+            .position(|display| display.is_main)
+            .expect("Layout::new checks for a main display")
+    }
+
+    /// Overlapping displays, then displays separated by a gap from the ones connected to
+    /// the main display
+    pub fn issues(&self) -> Vec<LayoutIssue> {
+        let mut issues = Vec::new();
+
+        for (index, display) in self.displays.iter().enumerate() {
+            for other in self.displays[index + 1..].iter() {
+                if display.bounds.overlaps(&other.bounds) {
+                    issues.push(LayoutIssue::Overlap(display.id, other.id));
+                }
+            }
+        }
+
+        let mut connected = vec![false; self.displays.len()];
+        let mut pending = vec![self.main_index()];
+        connected[self.main_index()] = true;
+        while let Some(index) = pending.pop() {
+            for (other, display) in self.displays.iter().enumerate() {
+                let bounds = &self.displays[index].bounds;
+                // Overlaps are reported on their own, so they do not also count as gaps
+                if !connected[other]
+                    && (display.bounds.touches(bounds) || display.bounds.overlaps(bounds))
+                {
+                    connected[other] = true;
+                    pending.push(other);
+                }
+            }
+        }
+        for (index, display) in self.displays.iter().enumerate() {
+            if !connected[index] {
+                issues.push(LayoutIssue::Detached(display.id));
+            }
+        }
+
+        issues
+    }
+
+    /// Move every display so the main display's origin is (0, 0)
+    pub fn normalize(&mut self) {
+        let offset = self.displays[self.main_index()].bounds.origin;
+        for display in self.displays.iter_mut() {
+            display.bounds.origin.x -= offset.x;
+            display.bounds.origin.y -= offset.y;
+        }
+    }
+
+    /// Move displays the shortest distance needed to remove overlaps and gaps
+    ///
+    /// The main display stays put. Other displays are placed in order of distance from
+    /// it; each keeps its position if it shares an edge with a placed display without
+    /// overlapping one, and otherwise moves to the nearest such position.
+    pub fn snap(&mut self) -> Result<(), Error> {
+        let main_index = self.main_index();
+        let main = self.displays[main_index].bounds;
+        let center_distance = |bounds: &Bounds| {
+            let dx = (2 * bounds.origin.x + bounds.width - 2 * main.origin.x - main.width) as i64;
+            let dy = (2 * bounds.origin.y + bounds.height - 2 * main.origin.y - main.height) as i64;
+            dx * dx + dy * dy
+        };
+
+        let mut order: Vec<usize> = (0..self.displays.len())
+            .filter(|index| *index != main_index)
+            .collect();
+        order.sort_by_key(|index| center_distance(&self.displays[*index].bounds));
+
+        let mut placed = vec![main];
+        for index in order {
+            let wanted = self.displays[index].bounds;
+            let fits = |bounds: &Bounds| !placed.iter().any(|other| bounds.overlaps(other));
+            let bounds = if fits(&wanted) && placed.iter().any(|other| wanted.touches(other)) {
+                wanted
+            } else {
+                adjacent_positions(&wanted, &placed)
+                    .into_iter()
+                    .filter(|candidate| fits(candidate))
+                    .min_by_key(|candidate| {
+                        let dx = (candidate.origin.x - wanted.origin.x) as i64;
+                        let dy = (candidate.origin.y - wanted.origin.y) as i64;
+                        dx * dx + dy * dy
+                    })
+                    .ok_or_else(|| {
+                        Error::Invalid(format!(
+                            "Display {} cannot be placed next to the other displays",
+                            self.displays[index].id
+                        ))
+                    })?
+            };
+            self.displays[index].bounds = bounds;
+            placed.push(bounds);
+        }
+        Ok(())
+    }
+
+    /// Snap displays together, then normalize so the main display is at (0, 0)
+    pub fn solve(&mut self) -> Result<(), Error> {
+        self.snap()?;
+        self.normalize();
+        Ok(())
+    }
+}
+
+/// Positions where `wanted` shares an edge with one of `placed`, as close as possible to
+/// where it was asked to be along that edge
+fn adjacent_positions(wanted: &Bounds, placed: &[Bounds]) -> Vec<Bounds> {
+    let mut positions = Vec::with_capacity(placed.len() * 4);
+    for other in placed {
+        let y = wanted
+            .origin
+            .y
+            .clamp(other.origin.y - wanted.height + 1, other.bottom() - 1);
+        let x = wanted
+            .origin
+            .x
+            .clamp(other.origin.x - wanted.width + 1, other.right() - 1);
+        positions.push(wanted.moved_to(Origin {
+            x: other.right(),
+            y,
+        }));
+        positions.push(wanted.moved_to(Origin {
+            x: other.origin.x - wanted.width,
+            y,
+        }));
+        positions.push(wanted.moved_to(Origin {
+            x,
+            y: other.origin.y - wanted.height,
+        }));
+        positions.push(wanted.moved_to(Origin {
+            x,
+            y: other.bottom(),
+        }));
+    }
+    positions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bounds(x: i32, y: i32, width: i32, height: i32) -> Bounds {
+        Bounds {
+            origin: Origin { x, y },
+            width,
+            height,
+        }
+    }
+
+    fn display(id: u32, bounds: Bounds, is_main: bool) -> LayoutDisplay {
+        LayoutDisplay {
+            id,
+            bounds,
+            is_main,
+        }
+    }
+
+    fn layout(displays: &[(u32, Bounds)]) -> Layout {
+        Layout::new(
+            displays
+                .iter()
+                .enumerate()
+                .map(|(index, (id, bounds))| display(*id, *bounds, index == 0))
+                .collect(),
+        )
+        .unwrap()
+    }
+
+    const MAIN: Bounds = Bounds {
+        origin: Origin { x: 0, y: 0 },
+        width: 1920,
+        height: 1080,
+    };
+
+    #[test]
+    fn overlapping_bounds() {
+        assert!(MAIN.overlaps(&bounds(1919, 0, 100, 100)));
+        assert!(MAIN.overlaps(&bounds(-10, -10, 3000, 3000)));
+        assert!(!MAIN.overlaps(&bounds(1920, 0, 100, 100)));
+        assert!(!MAIN.overlaps(&bounds(0, 1080, 100, 100)));
+    }
+
+    #[test]
+    fn touching_bounds() {
+        assert!(MAIN.touches(&bounds(1920, 500, 100, 100)));
+        assert!(MAIN.touches(&bounds(-100, 0, 100, 1080)));
+        assert!(MAIN.touches(&bounds(0, -100, 100, 100)));
+        assert!(MAIN.touches(&bounds(1000, 1080, 100, 100)));
+        // Meeting only at a corner leaves no edge to move the pointer across
+        assert!(!MAIN.touches(&bounds(1920, 1080, 100, 100)));
+        assert!(!MAIN.touches(&bounds(1921, 0, 100, 100)));
+        assert!(!MAIN.touches(&bounds(100, 100, 100, 100)));
+    }
+
+    #[test]
+    fn new_rejects_invalid_layouts() {
+        assert!(Layout::new(vec![display(1, MAIN, false)]).is_err());
+        assert!(Layout::new(vec![display(1, MAIN, true), display(2, MAIN, true)]).is_err());
+        assert!(Layout::new(vec![display(1, bounds(0, 0, 0, 1080), true)]).is_err());
+        assert!(
+            Layout::new(vec![
+                display(1, MAIN, true),
+                display(1, bounds(1920, 0, 100, 100), false)
+            ])
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn valid_layout_has_no_issues() {
+        let layout = layout(&[
+            (1, MAIN),
+            (2, bounds(1920, 0, 2560, 1440)),
+            (3, bounds(1920, 1440, 1280, 800)),
+        ]);
+        assert!(layout.issues().is_empty());
+    }
+
+    #[test]
+    fn overlapping_displays_are_reported() {
+        let layout = layout(&[(1, MAIN), (2, bounds(1800, 0, 2560, 1440))]);
+        assert_eq!(layout.issues(), vec![LayoutIssue::Overlap(1, 2)]);
+    }
+
+    #[test]
+    fn detached_displays_are_reported() {
+        let layout = layout(&[
+            (1, MAIN),
+            (2, bounds(2000, 0, 2560, 1440)),
+            (3, bounds(4560, 0, 1280, 800)),
+        ]);
+        // Display 3 touches display 2, but neither reaches the main display
+        assert_eq!(
+            layout.issues(),
+            vec![LayoutIssue::Detached(2), LayoutIssue::Detached(3)]
+        );
+    }
+
+    #[test]
+    fn snap_closes_gaps() {
+        let mut layout = layout(&[(1, MAIN), (2, bounds(2000, 100, 2560, 1440))]);
+        layout.snap().unwrap();
+        assert_eq!(layout.origin_of(2), Some(Origin { x: 1920, y: 100 }));
+        assert!(layout.issues().is_empty());
+    }
+
+    #[test]
+    fn snap_separates_overlaps() {
+        let mut layout = layout(&[(1, MAIN), (2, bounds(1800, -50, 2560, 1440))]);
+        layout.snap().unwrap();
+        assert_eq!(layout.origin_of(2), Some(Origin { x: 1920, y: -50 }));
+        assert!(layout.issues().is_empty());
+    }
+
+    #[test]
+    fn snap_keeps_valid_layouts() {
+        let displays = [
+            (1, MAIN),
+            (2, bounds(-2560, -360, 2560, 1440)),
+            (3, bounds(0, 1080, 1280, 800)),
+        ];
+        let mut snapped = layout(&displays);
+        snapped.snap().unwrap();
+        assert_eq!(snapped, layout(&displays));
+    }
+
+    #[test]
+    fn snap_places_every_display() {
+        // All displays asked for the main display's spot
+        let mut layout = layout(&[
+            (1, MAIN),
+            (2, MAIN),
+            (3, bounds(0, 0, 2560, 1440)),
+            (4, bounds(0, 0, 1280, 800)),
+            (5, bounds(0, 0, 1080, 1920)),
+        ]);
+        layout.snap().unwrap();
+        assert!(layout.issues().is_empty());
+        assert_eq!(layout.origin_of(1), Some(Origin { x: 0, y: 0 }));
+    }
+
+    #[test]
+    fn normalize_moves_main_display_to_origin() {
+        let mut layout = Layout::new(vec![
+            display(1, bounds(-1920, 0, 1920, 1080), false),
+            display(2, bounds(0, 100, 2560, 1440), true),
+        ])
+        .unwrap();
+        layout.normalize();
+        assert_eq!(layout.origin_of(1), Some(Origin { x: -1920, y: -100 }));
+        assert_eq!(layout.origin_of(2), Some(Origin { x: 0, y: 0 }));
+    }
+
+    #[test]
+    fn solve_snaps_then_normalizes() {
+        let mut layout = Layout::new(vec![
+            display(1, bounds(5000, 0, 1920, 1080), false),
+            display(2, bounds(100, 100, 1920, 1080), true),
+        ])
+        .unwrap();
+        layout.solve().unwrap();
+        // Display 1 keeps its height relative to where it asked to be
+        assert_eq!(layout.origin_of(1), Some(Origin { x: 1920, y: -100 }));
+        assert_eq!(layout.origin_of(2), Some(Origin { x: 0, y: 0 }));
+    }
+
+    #[test]
+    fn place_beside() {
+        let anchor = bounds(0, 0, 1920, 1080);
+        let place = |placement, alignment| place_relative(1280, 800, &anchor, placement, alignment);
+
+        assert_eq!(
+            place(Placement::RightOf, None).unwrap(),
+            Origin { x: 1920, y: 0 }
+        );
+        assert_eq!(
+            place(Placement::RightOf, Some(Alignment::Top)).unwrap(),
+            Origin { x: 1920, y: 0 }
+        );
+        assert_eq!(
+            place(Placement::RightOf, Some(Alignment::Bottom)).unwrap(),
+            Origin { x: 1920, y: 280 }
+        );
+        assert_eq!(
+            place(Placement::LeftOf, Some(Alignment::Center)).unwrap(),
+            Origin { x: -1280, y: 140 }
+        );
+    }
+
+    #[test]
+    fn place_above_and_below() {
+        let anchor = bounds(0, 0, 1920, 1080);
+        let place = |placement, alignment| place_relative(1280, 800, &anchor, placement, alignment);
+
+        assert_eq!(
+            place(Placement::Above, None).unwrap(),
+            Origin { x: 0, y: -800 }
+        );
+        assert_eq!(
+            place(Placement::Above, Some(Alignment::Left)).unwrap(),
+            Origin { x: 0, y: -800 }
+        );
+        assert_eq!(
+            place(Placement::Below, Some(Alignment::Right)).unwrap(),
+            Origin { x: 640, y: 1080 }
+        );
+        assert_eq!(
+            place(Placement::Below, Some(Alignment::Center)).unwrap(),
+            Origin { x: 320, y: 1080 }
+        );
+    }
+
+    #[test]
+    fn place_rejects_alignment_across_the_placement() {
+        let anchor = bounds(0, 0, 1920, 1080);
+        for placement in [Placement::RightOf, Placement::LeftOf] {
+            for alignment in [Alignment::Left, Alignment::Right] {
+                assert!(place_relative(1280, 800, &anchor, placement, Some(alignment)).is_err());
+            }
+        }
+        for placement in [Placement::Above, Placement::Below] {
+            for alignment in [Alignment::Top, Alignment::Bottom] {
+                assert!(place_relative(1280, 800, &anchor, placement, Some(alignment)).is_err());
+            }
+        }
+    }
+}
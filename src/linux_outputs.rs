@@ -0,0 +1,823 @@
+// Linux output configuration
+// Renders profiles as xrandr commands and kanshi, sway and Hyprland output configuration,
+// and reads kanshi and sway output configuration back into profiles. Linux tools size
+// modes in physical pixels with a separate scale, and position outputs in logical
+// pixels, which correspond to points on macOS. xrandr is the exception: X11 lays outputs
+// out in framebuffer pixels, so scaled outputs are given a matching `--scale`.
+
+use std::fmt::Write;
+
+use clap::ValueEnum;
+
+use crate::error::Error;
+use crate::layout::Origin;
+use crate::mode_descriptor::{ModeDescriptor, format_refresh_rate};
+use crate::profile::{DisplayState, Profile};
+
+/// Refresh rate assumed for imported modes that do not specify one
+const DEFAULT_REFRESH_RATE: f64 = 60.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    Xrandr,
+    Kanshi,
+    Sway,
+    Hyprland,
+    /// GNOME `monitors.xml`
+    Gnome,
+}
+
+/// Formats rendered from output settings by `render`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputFormat {
+    Xrandr,
+    Kanshi,
+    Sway,
+    Hyprland,
+}
+
+impl ExportFormat {
+    /// The output settings format, or `None` for GNOME, which identifies monitors by EDID
+    /// rather than output name and is rendered by `gnome_monitors` instead
+    pub fn output_format(self) -> Option<OutputFormat> {
+        match self {
+            ExportFormat::Xrandr => Some(OutputFormat::Xrandr),
+            ExportFormat::Kanshi => Some(OutputFormat::Kanshi),
+            ExportFormat::Sway => Some(OutputFormat::Sway),
+            ExportFormat::Hyprland => Some(OutputFormat::Hyprland),
+            ExportFormat::Gnome => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum ImportFormat {
+    Kanshi,
+    Sway,
+    /// GNOME `monitors.xml`
+    Gnome,
+}
+
+/// Settings for one output, in the terms Linux tools use
+#[derive(Clone, Debug, PartialEq)]
+pub struct OutputConfig {
+    /// Connector (`DP-1`) or description (`Dell Inc. DELL U2720Q 12345`)
+    pub name: String,
+    pub enabled: bool,
+    /// Mode size in physical pixels
+    pub mode: Option<(i32, i32)>,
+    pub refresh_rate: Option<f64>,
+    /// Position in logical pixels
+    pub position: Option<Origin>,
+    pub scale: Option<f32>,
+    /// Clockwise rotation in degrees
+    pub transform: Option<i32>,
+}
+
+impl OutputConfig {
+    fn new(name: &str) -> Self {
+        OutputConfig {
+            name: name.to_string(),
+            enabled: true,
+            mode: None,
+            refresh_rate: None,
+            position: None,
+            scale: None,
+            transform: None,
+        }
+    }
+
+    /// Describe a profile display; the output name defaults to the display name, then the UUID
+    pub fn from_display_state(state: &DisplayState) -> Self {
+        let name = state
+            .output
+            .clone()
+            .or_else(|| state.name.clone())
+            .unwrap_or_else(|| state.uuid.clone());
+
+        OutputConfig {
+            name,
+            enabled: true,
+            mode: state
+                .mode
+                .as_ref()
+                .map(|mode| (mode.pixels_wide, mode.pixels_high)),
+            refresh_rate: state.mode.as_ref().map(|mode| mode.refresh_rate),
+            position: state.origin,
+            scale: state.mode.as_ref().map(|mode| mode.scale),
+            transform: state.rotation,
+        }
+    }
+
+    /// Mode descriptor equivalent to this output's mode and scale
+    fn mode_descriptor(&self) -> Option<ModeDescriptor> {
+        let (pixels_wide, pixels_high) = self.mode?;
+        let scale = self.scale.unwrap_or(1.0);
+        Some(ModeDescriptor {
+            width: (pixels_wide as f32 / scale).round() as i32,
+            height: (pixels_high as f32 / scale).round() as i32,
+            pixels_wide,
+            pixels_high,
+            scale,
+            refresh_rate: self.refresh_rate.unwrap_or(DEFAULT_REFRESH_RATE),
+            hidpi: scale > 1.0,
+            depth: None,
+            hdr: false,
+        })
+    }
+
+    /// Copy this output's settings onto a profile display
+    pub fn update(&self, state: &mut DisplayState) {
+        state.output = Some(self.name.clone());
+        if let Some(mode) = self.mode_descriptor() {
+            state.mode = Some(mode);
+        }
+        if self.position.is_some() {
+            state.origin = self.position;
+        }
+        if self.transform.is_some() {
+            state.rotation = self.transform;
+        }
+    }
+}
+
+/// Render outputs in the syntax of `format`
+pub fn render(format: OutputFormat, profile_name: &str, outputs: &[OutputConfig]) -> String {
+    let mut out = String::new();
+
+    match format {
+        OutputFormat::Xrandr => {
+            out.push_str("xrandr");
+            for output in outputs {
+                let _ = write!(out, " \\\n  --output {}", shell_quote(&output.name));
+                if let Some((width, height)) = output.mode {
+                    let _ = write!(out, " --mode {}x{}", width, height);
+                }
+                if let Some(rate) = output.refresh_rate {
+                    let _ = write!(out, " --rate {}", format_refresh_rate(rate));
+                }
+                if let Some(position) = output.position {
+                    let _ = write!(out, " --pos {}x{}", position.x, position.y);
+                }
+                // Shrink a scaled output's footprint in the framebuffer to its logical size,
+                // so that positions in logical pixels line up
+                if let Some(scale) = output.scale.filter(|scale| *scale != 1.0) {
+                    let factor = 1.0 / scale;
+                    let _ = write!(out, " --scale {}x{}", factor, factor);
+                }
+                let rotate = match output.transform.unwrap_or(0) {
+                    90 => "right",
+                    180 => "inverted",
+                    270 => "left",
+                    _ => "normal",
+                };
+                let _ = write!(out, " --rotate {}", rotate);
+                if output.position == Some(Origin { x: 0, y: 0 }) {
+                    out.push_str(" --primary");
+                }
+            }
+            out.push('\n');
+        }
+        OutputFormat::Kanshi => {
+            let _ = writeln!(out, "profile {} {{", profile_name);
+            for output in outputs {
+                let _ = write!(out, "\toutput {}", quote(&output.name));
+                if let Some((width, height)) = output.mode {
+                    let _ = write!(out, " mode {}", mode_string(width, height, output));
+                }
+                if let Some(position) = output.position {
+                    let _ = write!(out, " position {},{}", position.x, position.y);
+                }
+                if let Some(scale) = output.scale {
+                    let _ = write!(out, " scale {}", scale);
+                }
+                let _ = writeln!(out, " transform {}", transform_name(output.transform));
+            }
+            out.push_str("}\n");
+        }
+        OutputFormat::Sway => {
+            let _ = writeln!(out, "# displayconfig profile '{}'", profile_name);
+            for output in outputs {
+                let _ = write!(out, "output {}", quote(&output.name));
+                if let Some((width, height)) = output.mode {
+                    let _ = write!(out, " mode {}", mode_string(width, height, output));
+                }
+                if let Some(position) = output.position {
+                    let _ = write!(out, " position {} {}", position.x, position.y);
+                }
+                if let Some(scale) = output.scale {
+                    let _ = write!(out, " scale {}", scale);
+                }
+                let _ = writeln!(out, " transform {}", transform_name(output.transform));
+            }
+        }
+        OutputFormat::Hyprland => {
+            let _ = writeln!(out, "# displayconfig profile '{}'", profile_name);
+            for output in outputs {
+                let mode = match output.mode {
+                    Some((width, height)) => match output.refresh_rate {
+                        Some(rate) => format!("{}x{}@{}", width, height, format_refresh_rate(rate)),
+                        None => format!("{}x{}", width, height),
+                    },
+                    None => "preferred".to_string(),
+                };
+                let position = match output.position {
+                    Some(position) => format!("{}x{}", position.x, position.y),
+                    None => "auto".to_string(),
+                };
+                let _ = write!(
+                    out,
+                    "monitor = {}, {}, {}, {}",
+                    output.name,
+                    mode,
+                    position,
+                    output.scale.unwrap_or(1.0)
+                );
+                match output.transform.unwrap_or(0) {
+                    0 => out.push('\n'),
+                    degrees => {
+                        let _ = writeln!(out, ", transform, {}", degrees / 90);
+                    }
+                }
+            }
+        }
+    }
+
+    out
+}
+
+fn mode_string(width: i32, height: i32, output: &OutputConfig) -> String {
+    match output.refresh_rate {
+        Some(rate) => format!("{}x{}@{}Hz", width, height, format_refresh_rate(rate)),
+        None => format!("{}x{}", width, height),
+    }
+}
+
+fn transform_name(degrees: Option<i32>) -> String {
+    match degrees.unwrap_or(0) {
+        0 => "normal".to_string(),
+        degrees => degrees.to_string(),
+    }
+}
+
+/// Double-quote a name for kanshi and sway when it contains spaces
+fn quote(name: &str) -> String {
+    if name.contains(char::is_whitespace) || name.is_empty() {
+        format!("\"{}\"", name.replace('"', "\\\""))
+    } else {
+        name.to_string()
+    }
+}
+
+/// Single-quote a name for a POSIX shell when needed
+fn shell_quote(name: &str) -> String {
+    if name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || "-_./:".contains(c))
+        && !name.is_empty()
+    {
+        name.to_string()
+    } else {
+        format!("'{}'", name.replace('\'', "'\\''"))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Word(String),
+    Open,
+    Close,
+    /// End of a statement (newline or `;`)
+    End,
+}
+
+/// Split kanshi or sway configuration into tokens, dropping comments
+fn tokenize(text: &str) -> Result<Vec<Token>, Error> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '#' => {
+                while chars.peek().is_some_and(|c| *c != '\n') {
+                    chars.next();
+                }
+            }
+            '\n' | ';' => tokens.push(Token::End),
+            '{' => tokens.push(Token::Open),
+            '}' => tokens.push(Token::Close),
+            '\\' if chars.peek() == Some(&'\n') => {
+                chars.next();
+            }
+            '"' | '\'' => {
+                let mut word = String::new();
+                loop {
+                    match chars.next() {
+                        Some('\\') if c == '"' => {
+                            if let Some(next) = chars.next() {
+                                word.push(next);
+                            }
+                        }
+                        Some(q) if q == c => break,
+                        Some(other) => word.push(other),
+                        None => {
+                            return Err(Error::Format(
+                                "Unterminated quote in output configuration".to_string(),
+                            ));
+                        }
+                    }
+                }
+                tokens.push(Token::Word(word));
+            }
+            c if c.is_whitespace() => {}
+            c => {
+                let mut word = c.to_string();
+                while let Some(next) = chars.peek() {
+                    if next.is_whitespace() || "{};#".contains(*next) {
+                        break;
+                    }
+                    word.push(*next);
+                    chars.next();
+                }
+                tokens.push(Token::Word(word));
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// A statement: its words and, if present, the statements of its `{ … }` block
+#[derive(Debug)]
+struct Statement {
+    words: Vec<String>,
+    block: Vec<Statement>,
+}
+
+fn parse_statements(tokens: &[Token], pos: &mut usize) -> Result<Vec<Statement>, Error> {
+    let mut statements = Vec::new();
+    let mut words = Vec::new();
+
+    while *pos < tokens.len() {
+        let token = &tokens[*pos];
+        *pos += 1;
+        match token {
+            Token::Word(word) => words.push(word.clone()),
+            Token::End => {
+                if !words.is_empty() {
+                    statements.push(Statement {
+                        words: std::mem::take(&mut words),
+                        block: Vec::new(),
+                    });
+                }
+            }
+            Token::Open => {
+                let block = parse_statements(tokens, pos)?;
+                statements.push(Statement {
+                    words: std::mem::take(&mut words),
+                    block,
+                });
+            }
+            Token::Close => {
+                if !words.is_empty() {
+                    statements.push(Statement {
+                        words: std::mem::take(&mut words),
+                        block: Vec::new(),
+                    });
+                }
+                return Ok(statements);
+            }
+        }
+    }
+
+    if !words.is_empty() {
+        statements.push(Statement {
+            words,
+            block: Vec::new(),
+        });
+    }
+    Ok(statements)
+}
+
+fn parse_config(text: &str) -> Result<Vec<Statement>, Error> {
+    let tokens = tokenize(text)?;
+    let mut pos = 0;
+    parse_statements(&tokens, &mut pos)
+}
+
+/// Apply output parameters such as `mode 1920x1080@60Hz position 0,0` to `output`
+///
+/// Parsing stops at the first unknown parameter, since its arguments cannot be told
+/// apart from the parameters that follow.
+fn apply_output_params(output: &mut OutputConfig, words: &[String]) -> Result<(), Error> {
+    let invalid = |what: &str| {
+        Error::Format(format!(
+            "Invalid {} for output '{}': {}",
+            what,
+            output.name,
+            words.join(" ")
+        ))
+    };
+
+    let mut i = 0;
+    while i < words.len() {
+        let key = words[i].as_str();
+        let arg = words.get(i + 1).map(String::as_str);
+        match key {
+            "enable" => output.enabled = true,
+            "disable" => output.enabled = false,
+            "mode" | "res" | "resolution" => {
+                // sway allows `mode --custom WxH`
+                let mut value = arg.ok_or_else(|| invalid("mode"))?;
+                if value == "--custom" {
+                    i += 1;
+                    value = words.get(i + 1).ok_or_else(|| invalid("mode"))?;
+                }
+                let (size, rate) = match value.split_once('@') {
+                    Some((size, rate)) => (size, Some(rate.trim_end_matches("Hz"))),
+                    None => (value, None),
+                };
+                let (width, height) = size.split_once('x').ok_or_else(|| invalid("mode"))?;
+                output.mode = Some((
+                    width.parse().map_err(|_| invalid("mode"))?,
+                    height.parse().map_err(|_| invalid("mode"))?,
+                ));
+                output.refresh_rate = rate
+                    .map(|rate| rate.parse().map_err(|_| invalid("mode")))
+                    .transpose()?;
+                i += 2;
+                continue;
+            }
+            "position" | "pos" => {
+                // kanshi uses `X,Y`, sway uses `X Y`
+                let value = arg.ok_or_else(|| invalid("position"))?;
+                let (x, y, used) = match value.split_once(',') {
+                    Some((x, y)) => (x, y, 2),
+                    None => (
+                        value,
+                        words
+                            .get(i + 2)
+                            .map(String::as_str)
+                            .ok_or_else(|| invalid("position"))?,
+                        3,
+                    ),
+                };
+                output.position = Some(Origin {
+                    x: x.parse().map_err(|_| invalid("position"))?,
+                    y: y.parse().map_err(|_| invalid("position"))?,
+                });
+                i += used;
+                continue;
+            }
+            "scale" => {
+                let value = arg.ok_or_else(|| invalid("scale"))?;
+                output.scale = Some(value.parse().map_err(|_| invalid("scale"))?);
+                i += 2;
+                continue;
+            }
+            "transform" => {
+                let degrees = match arg.ok_or_else(|| invalid("transform"))? {
+                    "normal" | "0" => 0,
+                    "90" => 90,
+                    "180" => 180,
+                    "270" => 270,
+                    _ => return Err(invalid("transform")),
+                };
+                i += 2;
+                // sway allows a trailing `clockwise` / `anticlockwise`, which rotates
+                // relative to the output's transform so far
+                let current = output.transform.unwrap_or(0);
+                output.transform = Some(match words.get(i).map(String::as_str) {
+                    Some("clockwise") => {
+                        i += 1;
+                        (current + degrees) % 360
+                    }
+                    Some("anticlockwise") => {
+                        i += 1;
+                        (current + 360 - degrees) % 360
+                    }
+                    _ => degrees,
+                });
+                continue;
+            }
+            "adaptive_sync" | "alias" | "subpixel" | "max_render_time" | "render_bit_depth"
+            | "dpms" | "power" => {
+                i += 2;
+                continue;
+            }
+            _ => break,
+        }
+        i += 1;
+    }
+
+    Ok(())
+}
+
+/// Build an output from an `output <name> …` statement
+fn parse_output(statement: &Statement) -> Result<Option<OutputConfig>, Error> {
+    let Some(name) = statement.words.get(1) else {
+        return Err(Error::Format("Output statement without a name".to_string()));
+    };
+    // Wildcard defaults do not describe a particular display
+    if name == "*" {
+        return Ok(None);
+    }
+
+    let mut output = OutputConfig::new(name);
+    apply_output_params(&mut output, &statement.words[2..])?;
+    for line in statement.block.iter() {
+        apply_output_params(&mut output, &line.words)?;
+    }
+    Ok(Some(output))
+}
+
+/// Read the outputs of a kanshi profile; the first profile is used when `profile` is `None`
+pub fn parse_kanshi(text: &str, profile: Option<&str>) -> Result<Vec<OutputConfig>, Error> {
+    let statements = parse_config(text)?;
+
+    let block = statements
+        .iter()
+        .filter(|statement| {
+            statement
+                .words
+                .first()
+                .is_some_and(|word| word == "profile")
+        })
+        .find(|statement| match profile {
+            Some(name) => statement.words.get(1).is_some_and(|word| word == name),
+            None => true,
+        })
+        .ok_or_else(|| match profile {
+            Some(name) => Error::NotFound(format!("kanshi profile '{}' not found", name)),
+            None => Error::NotFound("No kanshi profile found".to_string()),
+        })?;
+
+    let mut outputs = Vec::new();
+    for statement in block.block.iter() {
+        if statement.words.first().is_some_and(|word| word == "output")
+            && let Some(output) = parse_output(statement)?
+        {
+            outputs.push(output);
+        }
+    }
+    Ok(outputs)
+}
+
+/// Read the `output` directives of a sway configuration
+pub fn parse_sway(text: &str) -> Result<Vec<OutputConfig>, Error> {
+    let mut outputs = Vec::new();
+    for statement in parse_config(text)?.iter() {
+        if statement.words.first().is_some_and(|word| word == "output")
+            && let Some(output) = parse_output(statement)?
+        {
+            outputs.push(output);
+        }
+    }
+    Ok(outputs)
+}
+
+/// Merge imported outputs into a profile
+///
+/// Outputs are matched to existing displays by output name, then by display name, so
+/// displays keep their persistent screen ids. Unmatched outputs are added with the output
+/// name standing in for the UUID until the profile is saved again on macOS.
+pub fn merge_outputs(profile: &mut Profile, outputs: &[OutputConfig]) {
+    for output in outputs.iter().filter(|output| output.enabled) {
+        let existing = profile.displays.iter_mut().find(|state| {
+            state
+                .output
+                .as_ref()
+                .or(state.name.as_ref())
+                .is_some_and(|name| name.eq_ignore_ascii_case(&output.name))
+        });
+
+        match existing {
+            Some(state) => output.update(state),
+            None => {
+                let mut state = DisplayState {
+                    uuid: output.name.clone(),
+                    name: None,
+                    output: None,
+                    monitor: None,
+                    brightness: None,
+                    rotation: None,
+                    origin: None,
+                    mode: None,
+                    preset: None,
+                    hdr: None,
+                };
+                output.update(&mut state);
+                profile.displays.push(state);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DELL: &str = "Dell Inc. DELL U2720Q 12345";
+
+    /// A laptop panel at 2x next to an external display at 1x, as exported from a profile
+    fn laptop_and_external() -> Vec<OutputConfig> {
+        vec![
+            OutputConfig {
+                name: "eDP-1".to_string(),
+                enabled: true,
+                mode: Some((2880, 1800)),
+                refresh_rate: Some(60.0),
+                position: Some(Origin { x: 0, y: 0 }),
+                scale: Some(2.0),
+                transform: Some(0),
+            },
+            OutputConfig {
+                name: DELL.to_string(),
+                enabled: true,
+                mode: Some((1920, 1080)),
+                refresh_rate: Some(59.94),
+                position: Some(Origin { x: 1440, y: 0 }),
+                scale: Some(1.0),
+                transform: Some(90),
+            },
+        ]
+    }
+
+    fn state(uuid: &str, output: Option<&str>, name: Option<&str>) -> DisplayState {
+        DisplayState {
+            uuid: uuid.to_string(),
+            name: name.map(str::to_string),
+            output: output.map(str::to_string),
+            monitor: None,
+            brightness: None,
+            rotation: None,
+            origin: None,
+            mode: None,
+            preset: None,
+            hdr: None,
+        }
+    }
+
+    #[test]
+    fn kanshi_round_trip() {
+        let outputs = laptop_and_external();
+        let text = render(OutputFormat::Kanshi, "desk", &outputs);
+        assert!(text.contains(&format!("output \"{}\"", DELL)), "{}", text);
+        assert_eq!(parse_kanshi(&text, Some("desk")).unwrap(), outputs);
+    }
+
+    #[test]
+    fn sway_round_trip() {
+        let outputs = laptop_and_external();
+        let text = render(OutputFormat::Sway, "desk", &outputs);
+        assert_eq!(parse_sway(&text).unwrap(), outputs);
+    }
+
+    #[test]
+    fn kanshi_profile_selection() {
+        let text = "\
+profile undocked {
+\toutput eDP-1 enable scale 2
+}
+
+profile docked {
+\toutput eDP-1 disable
+\toutput 'Dell Inc. DELL U2720Q 12345' mode 3840x2160 position 0,0
+}
+";
+        let first = parse_kanshi(text, None).unwrap();
+        assert_eq!(first.len(), 1);
+        assert_eq!(first[0].scale, Some(2.0));
+
+        let docked = parse_kanshi(text, Some("docked")).unwrap();
+        assert!(!docked[0].enabled);
+        assert_eq!(docked[1].name, DELL);
+        assert_eq!(docked[1].mode, Some((3840, 2160)));
+        assert_eq!(docked[1].refresh_rate, None);
+
+        assert!(matches!(
+            parse_kanshi(text, Some("travel")),
+            Err(Error::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn sway_blocks_and_quoted_descriptions() {
+        let text = "\
+# Laptop
+output * bg ~/wall.png fill
+output \"Dell Inc. DELL U2720Q 12345\" {
+    mode --custom 2560x1440@59.951Hz
+    pos 1440 0
+}
+output eDP-1 res 2880x1800 scale 2; output HDMI-A-1 disable
+";
+        let outputs = parse_sway(text).unwrap();
+        assert_eq!(outputs.len(), 3);
+        assert_eq!(outputs[0].name, DELL);
+        assert_eq!(outputs[0].mode, Some((2560, 1440)));
+        assert_eq!(outputs[0].refresh_rate, Some(59.951));
+        assert_eq!(outputs[0].position, Some(Origin { x: 1440, y: 0 }));
+        assert_eq!(outputs[1].scale, Some(2.0));
+        assert!(!outputs[2].enabled);
+    }
+
+    #[test]
+    fn relative_transforms() {
+        let outputs = parse_sway(
+            "output DP-1 transform 90\n\
+             output DP-1 transform 180 clockwise\n\
+             output DP-2 transform 90 clockwise\n\
+             output DP-3 transform 90 anticlockwise\n\
+             output DP-4 transform 270 transform 180 anticlockwise",
+        )
+        .unwrap();
+        let transforms: Vec<_> = outputs.iter().map(|output| output.transform).collect();
+        // Each statement starts from the default transform
+        assert_eq!(
+            transforms,
+            vec![Some(90), Some(180), Some(90), Some(270), Some(90)]
+        );
+    }
+
+    #[test]
+    fn invalid_parameters() {
+        assert!(matches!(
+            parse_sway("output DP-1 transform flipped"),
+            Err(Error::Format(_))
+        ));
+        assert!(matches!(
+            parse_sway("output DP-1 mode 1920"),
+            Err(Error::Format(_))
+        ));
+        assert!(matches!(
+            parse_sway("output \"DP-1 mode 1920x1080"),
+            Err(Error::Format(_))
+        ));
+    }
+
+    #[test]
+    fn xrandr_scales_hidpi_outputs() {
+        let text = render(OutputFormat::Xrandr, "desk", &laptop_and_external());
+        assert_eq!(
+            text,
+            "xrandr \\\n  \
+             --output eDP-1 --mode 2880x1800 --rate 60 --pos 0x0 --scale 0.5x0.5 \
+             --rotate normal --primary \\\n  \
+             --output 'Dell Inc. DELL U2720Q 12345' --mode 1920x1080 --rate 59.94 \
+             --pos 1440x0 --rotate right\n"
+        );
+    }
+
+    #[test]
+    fn hyprland() {
+        let text = render(OutputFormat::Hyprland, "desk", &laptop_and_external());
+        assert_eq!(
+            text,
+            "# displayconfig profile 'desk'\n\
+             monitor = eDP-1, 2880x1800@60, 0x0, 2\n\
+             monitor = Dell Inc. DELL U2720Q 12345, 1920x1080@59.94, 1440x0, 1, transform, 1\n"
+        );
+    }
+
+    #[test]
+    fn merge_matches_output_names() {
+        let mut profile = Profile {
+            name: "desk".to_string(),
+            displays: vec![
+                state("LAPTOP-UUID", Some("eDP-1"), Some("Color LCD")),
+                state("DELL-UUID", None, Some(DELL)),
+            ],
+        };
+        merge_outputs(&mut profile, &laptop_and_external());
+
+        assert_eq!(profile.displays.len(), 2);
+        let laptop = &profile.displays[0];
+        assert_eq!(laptop.uuid, "LAPTOP-UUID");
+        let mode = laptop.mode.as_ref().unwrap();
+        assert_eq!((mode.width, mode.height), (1440, 900));
+        assert!(mode.hidpi);
+        let dell = &profile.displays[1];
+        assert_eq!(dell.uuid, "DELL-UUID");
+        assert_eq!(dell.output.as_deref(), Some(DELL));
+        assert_eq!(dell.origin, Some(Origin { x: 1440, y: 0 }));
+        assert_eq!(dell.rotation, Some(90));
+    }
+
+    #[test]
+    fn merge_adds_unknown_outputs_and_skips_disabled_ones() {
+        let mut profile = Profile {
+            name: "desk".to_string(),
+            displays: vec![state("LAPTOP-UUID", Some("eDP-1"), None)],
+        };
+        let mut outputs = laptop_and_external();
+        outputs[0].enabled = false;
+        outputs[1].name = "DP-2".to_string();
+        merge_outputs(&mut profile, &outputs);
+
+        assert_eq!(profile.displays.len(), 2);
+        assert_eq!(profile.displays[0].mode, None);
+        assert_eq!(profile.displays[1].uuid, "DP-2");
+        assert_eq!(profile.displays[1].output.as_deref(), Some("DP-2"));
+    }
+}
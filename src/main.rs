@@ -1,19 +1,118 @@
 // Display management utility for macOS
 // Uses Core Graphics and MonitorPanel.framework APIs
 
-use clap::{Parser, Subcommand};
+use std::path::PathBuf;
+use std::time::Duration;
 
+use clap::{ArgGroup, Parser, Subcommand, ValueEnum};
+
+mod arrangement;
+mod brightness;
+mod desired_state;
+mod diagram;
+mod display_kind;
+mod display_name;
+mod displayplacer;
+mod error;
+mod gnome_monitors;
+mod layout;
+mod linux_outputs;
+mod mirror_plan;
+mod mode_descriptor;
+mod mode_safety;
+mod mode_set;
 mod monitor_panel;
+mod preset;
+mod profile;
+mod reconfigure_lock;
+mod rescan;
+mod rotation;
+mod selector;
 
 mod commands;
 use crate::commands::{
-    get_brightness, get_display_mode, list_displays, set_brightness, set_display_mode, watch,
+    AutoProfileOptions, Position, ResetOptions, apply_desired_state, arrange, displayplacer_export,
+    displayplacer_import, get_brightness, get_display_mode, list_displays, mirror_displays,
+    plan_mirror_displays, preset_get, preset_list, preset_set, profile_apply, profile_delete,
+    profile_export, profile_import, profile_list, profile_save, profile_show, rescan,
+    rescan_before_command, reset_display, rotate, set_brightness, set_display_mode, set_hdr,
+    set_main, set_overscan, set_refresh_rate, set_underscan, show_layout, stop_mirroring, watch,
+};
+use crate::display_kind::DisplayTag;
+use crate::layout::{Alignment, Origin, Placement};
+use crate::linux_outputs::{ExportFormat, ImportFormat};
+use crate::mode_set::{
+    DEFAULT_RETRIES, DEFAULT_RETRY_DELAY_MS, MAX_RETRIES, MAX_RETRY_DELAY_MS, RetryPolicy,
 };
+use crate::reconfigure_lock::{DEFAULT_WAIT_TIMEOUT_SECS, MAX_WAIT_TIMEOUT_SECS, ReconfigureLock};
+
+/// On/off argument for display settings
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum Switch {
+    On,
+    Off,
+}
+
+impl Switch {
+    fn enabled(self) -> bool {
+        self == Switch::On
+    }
+}
 
 #[derive(Parser)]
 #[command(name = "displayconfig")]
 #[command(author, version, about = "Display management utility for macOS", long_about = None)]
 struct Cli {
+    /// Make MonitorPanel enumerate displays and modes again before running the command
+    #[arg(long, global = true)]
+    rescan: bool,
+
+    /// Allow mode changes in safe mode and to modes that are hidden, interlaced or stretched
+    #[arg(long, global = true)]
+    force: bool,
+
+    /// Wait for another displayconfig that is reconfiguring displays (default)
+    ///
+    /// Runs as root and user sessions only wait for each other once a run as root has
+    /// created /var/run/displayconfig.lock; until then each user locks a file in their own
+    /// temporary directory.
+    #[arg(long, global = true, overrides_with = "no_wait")]
+    wait: bool,
+
+    /// Fail instead of waiting when another displayconfig is reconfiguring displays
+    #[arg(long, global = true, overrides_with = "wait")]
+    no_wait: bool,
+
+    /// Give up waiting for another displayconfig after this many seconds (at most an hour)
+    #[arg(
+        long,
+        global = true,
+        value_name = "SECONDS",
+        default_value_t = DEFAULT_WAIT_TIMEOUT_SECS,
+        value_parser = clap::value_parser!(u64).range(..=MAX_WAIT_TIMEOUT_SECS)
+    )]
+    wait_timeout: u64,
+
+    /// How often to retry a mode change that failed because the display was asleep or busy
+    #[arg(
+        long,
+        global = true,
+        value_name = "N",
+        default_value_t = DEFAULT_RETRIES,
+        value_parser = clap::value_parser!(u32).range(..=MAX_RETRIES as i64)
+    )]
+    retries: u32,
+
+    /// Delay before the first mode change retry; doubled before each later one, up to 10s
+    #[arg(
+        long,
+        global = true,
+        value_name = "MS",
+        default_value_t = DEFAULT_RETRY_DELAY_MS,
+        value_parser = clap::value_parser!(u64).range(..=MAX_RETRY_DELAY_MS)
+    )]
+    retry_delay_ms: u64,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -25,19 +124,34 @@ enum Commands {
         #[arg(short, long)]
         verbose: bool,
 
-        /// Filter by display ID
+        /// Only list the displays a selector matches (`main`, `builtin`, `external`, a kind
+        /// tag such as `tv` or `tag:4k`, `id:N`, a UUID or `name:…`)
         #[arg(short, long)]
-        display: Option<u32>,
+        display: Option<String>,
+
+        /// Only list displays with this kind tag
+        #[arg(long = "type", value_enum, value_name = "TAG")]
+        kind: Option<DisplayTag>,
+
+        /// Draw the display arrangement instead of listing details
+        #[arg(long, conflicts_with_all = ["verbose", "display", "kind"])]
+        layout: bool,
+
+        /// Print the displays as JSON
+        #[arg(long, conflicts_with_all = ["verbose", "layout"])]
+        json: bool,
     },
     /// Get the current mode number for a specific display
     GetMode {
-        /// Persistent screen ID (UUID) to query
+        /// Display to query (`main`, `builtin`, a kind tag such as
+        /// `tv` or `tag:4k`, `id:N`, a UUID or `name:…`)
         #[arg(short, long)]
         display: String,
     },
     /// Set the display mode for a specific display
     SetMode {
-        /// Persistent screen ID (UUID) to configure
+        /// Display to configure (`main`, `builtin`, a kind tag such as
+        /// `tv` or `tag:4k`, `id:N`, a UUID or `name:…`)
         #[arg(short, long)]
         display: String,
 
@@ -45,17 +159,53 @@ enum Commands {
         #[arg(short, long)]
         mode: i32,
     },
+    /// Change the refresh rate, keeping the current resolution and scale
+    SetRefresh {
+        /// Display to configure (`main`, `builtin`, a kind tag such as
+        /// `tv` or `tag:4k`, `id:N`, a UUID or `name:…`)
+        #[arg(short, long)]
+        display: String,
+
+        /// Refresh rate in Hz, e.g. 60 or 59.94
+        #[arg(long)]
+        hz: f64,
+    },
+    /// Return a display to its default mode
+    Reset {
+        /// Display to reset (`main`, `builtin`, a kind tag such as
+        /// `tv` or `tag:4k`, `id:N`, a UUID or `name:…`)
+        #[arg(short, long)]
+        display: String,
+
+        /// Use the native mode (the panel's own pixels) instead of the default mode
+        #[arg(long)]
+        native: bool,
+
+        /// Also set brightness to 100%
+        #[arg(long)]
+        brightness: bool,
+
+        /// Also remove underscan
+        #[arg(long)]
+        underscan: bool,
+
+        /// Also rotate back to 0°
+        #[arg(long)]
+        rotation: bool,
+    },
     /// Get the current brightness percentage for displays
     GetBrightness {
-        /// Filter by display ID
+        /// Only show the displays a selector matches (`main`, `builtin`, `external`, a kind
+        /// tag such as `tv` or `tag:4k`, `id:N`, a UUID or `name:…`)
         #[arg(short, long)]
-        display: Option<u32>,
+        display: Option<String>,
     },
     /// Set the brightness percentage for a specific display
     SetBrightness {
-        /// Display ID to configure
+        /// Display to configure (`main`, `builtin`, a kind tag such as
+        /// `tv` or `tag:4k`, `id:N`, a UUID or `name:…`)
         #[arg(short, long)]
-        display: u32,
+        display: String,
 
         /// Brightness percentage (0-100)
         #[arg(short, long)]
@@ -63,33 +213,501 @@ enum Commands {
     },
 
     /// Watch for display configuration changes and print events.
-    Watch {},
+    Watch {
+        /// Apply the saved profile matching the connected displays whenever they change
+        #[arg(long)]
+        auto_profile: bool,
+
+        /// Profile to apply when no saved profile matches the connected displays
+        #[arg(long, requires = "auto_profile")]
+        fallback: Option<String>,
+
+        /// Milliseconds without reconfiguration events before a profile is chosen
+        #[arg(long, default_value_t = 2000, requires = "auto_profile")]
+        settle_ms: u64,
+    },
+
+    /// Bring displays to the desired state described in a TOML file
+    ///
+    /// Exits 0 when already compliant, 2 when changes were made and 1 on errors.
+    Apply {
+        /// Desired state file
+        #[arg(short, long)]
+        file: PathBuf,
+
+        /// Report the changes that would be made without applying them
+        #[arg(long)]
+        check: bool,
+
+        /// Print the result as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Move a display to an origin or next to another display
+    #[command(group(
+        ArgGroup::new("position")
+            .required(true)
+            .args(["origin", "right_of", "left_of", "above", "below"])
+    ))]
+    Arrange {
+        /// Display to move (`main`, `builtin`, a kind tag such as
+        /// `tv` or `tag:4k`, `id:N`, a UUID or `name:…`)
+        #[arg(short, long)]
+        display: String,
+
+        /// Absolute origin of the display's top-left corner, as X,Y
+        #[arg(long, allow_hyphen_values = true)]
+        origin: Option<Origin>,
+
+        /// Place the display right of this display
+        #[arg(long, value_name = "DISPLAY")]
+        right_of: Option<String>,
+
+        /// Place the display left of this display
+        #[arg(long, value_name = "DISPLAY")]
+        left_of: Option<String>,
+
+        /// Place the display above this display
+        #[arg(long, value_name = "DISPLAY")]
+        above: Option<String>,
+
+        /// Place the display below this display
+        #[arg(long, value_name = "DISPLAY")]
+        below: Option<String>,
+
+        /// Edge to line up with the other display (top, bottom or center beside it; left,
+        /// right or center above or below it)
+        #[arg(long, value_enum, conflicts_with = "origin")]
+        align: Option<Alignment>,
+    },
+
+    /// Make a display the main display, which shows the menu bar
+    SetMain {
+        /// Display to make main (`builtin`, `external`, a kind tag such as
+        /// `tv` or `tag:4k`, `id:N`, a UUID or `name:…`)
+        #[arg(short, long)]
+        display: String,
+    },
+
+    /// Rotate a display
+    Rotate {
+        /// Display to rotate (`main`, `builtin`, a kind tag such as
+        /// `tv` or `tag:4k`, `id:N`, a UUID or `name:…`)
+        #[arg(short, long)]
+        display: String,
+
+        /// Rotation in degrees clockwise: 0, 90, 180 or 270
+        #[arg(long)]
+        degrees: i32,
+    },
+
+    /// Set the underscan of a display, e.g. a TV that crops the picture edges
+    Underscan {
+        /// Display to configure (`main`, `builtin`, a kind tag such as
+        /// `tv` or `tag:4k`, `id:N`, a UUID or `name:…`)
+        #[arg(short, long)]
+        display: String,
+
+        /// Underscan value, within the display's range shown by `list --verbose`
+        #[arg(long, allow_hyphen_values = true)]
+        value: i32,
+    },
+
+    /// Turn overscan on or off for a display
+    Overscan {
+        /// Display to configure (`main`, `builtin`, a kind tag such as
+        /// `tv` or `tag:4k`, `id:N`, a UUID or `name:…`)
+        #[arg(short, long)]
+        display: String,
+
+        #[arg(value_enum)]
+        state: Switch,
+    },
+
+    /// Turn HDR on or off for a display with HDR modes
+    Hdr {
+        /// Display to configure (`main`, `builtin`, a kind tag such as
+        /// `tv` or `tag:4k`, `id:N`, a UUID or `name:…`)
+        #[arg(short, long)]
+        display: String,
+
+        #[arg(value_enum)]
+        state: Switch,
+    },
+
+    /// Mirror displays, or stop mirroring
+    Mirror {
+        /// Display whose picture the others show
+        #[arg(long, required_unless_present = "off", conflicts_with = "off")]
+        source: Option<String>,
+
+        /// Displays to mirror the source onto, comma-separated (defaults to all others)
+        #[arg(long, value_delimiter = ',', requires = "source")]
+        targets: Vec<String>,
+
+        /// Let macOS choose the best mode for the mirror set
+        #[arg(long, requires = "source")]
+        best_mode: bool,
+
+        /// Rank the resolutions the displays share and show the modes each would use,
+        /// without mirroring
+        #[arg(long, requires = "source", conflicts_with = "best_mode")]
+        plan: bool,
+
+        /// Stop mirroring this display, or all displays when none is given
+        #[arg(long, num_args = 0..=1, value_name = "DISPLAY")]
+        off: Option<Option<String>>,
+    },
+
+    /// List, show or activate reference presets (Pro Display XDR, MacBook Pro)
+    Preset {
+        #[command(subcommand)]
+        command: PresetCommands,
+    },
+
+    /// Enumerate displays and modes again and report what changed
+    Rescan,
+
+    /// Draw the display arrangement
+    Layout {
+        /// Write the layout to this SVG file instead of printing it
+        #[arg(long)]
+        svg: Option<PathBuf>,
+    },
+
+    /// Save and restore named display configurations
+    Profile {
+        #[command(subcommand)]
+        command: ProfileCommands,
+    },
+
+    /// Convert to and from displayplacer command strings
+    Displayplacer {
+        #[command(subcommand)]
+        command: DisplayplacerCommands,
+    },
+}
+
+#[derive(Subcommand)]
+enum PresetCommands {
+    /// List the presets a display offers
+    List {
+        /// Display to inspect (`main`, `builtin`, a kind tag such as
+        /// `tv` or `tag:4k`, `id:N`, a UUID or `name:…`)
+        #[arg(short, long)]
+        display: String,
+    },
+    /// Show the active preset
+    Get {
+        /// Display to inspect (`main`, `builtin`, a kind tag such as
+        /// `tv` or `tag:4k`, `id:N`, a UUID or `name:…`)
+        #[arg(short, long)]
+        display: String,
+    },
+    /// Activate a preset by name
+    Set {
+        /// Display to configure (`main`, `builtin`, a kind tag such as
+        /// `tv` or `tag:4k`, `id:N`, a UUID or `name:…`)
+        #[arg(short, long)]
+        display: String,
+
+        /// Preset name as shown by `preset list`
+        name: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum DisplayplacerCommands {
+    /// Convert displayplacer arguments into a desired state file or a profile
+    Import {
+        /// displayplacer command line or its quoted arguments
+        #[arg(required = true, num_args = 1..)]
+        args: Vec<String>,
+
+        /// Write the desired state to this file instead of printing it
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Save as a named profile, resolving modes on the connected displays
+        #[arg(long, conflicts_with = "output")]
+        profile: Option<String>,
+    },
+    /// Print the current configuration as a displayplacer command
+    Export {
+        /// Export a saved profile instead of the current configuration
+        #[arg(long)]
+        profile: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ProfileCommands {
+    /// Save the current mode, preset, HDR, brightness, rotation and position of all displays
+    Save {
+        /// Profile name
+        name: String,
+    },
+    /// Restore a saved profile
+    Apply {
+        /// Profile name
+        name: String,
+
+        /// Use the closest available mode when a saved mode has no exact match
+        #[arg(long)]
+        closest: bool,
+    },
+    /// List saved profiles
+    List,
+    /// Show the contents of a saved profile
+    Show {
+        /// Profile name
+        name: String,
+    },
+    /// Delete a saved profile
+    Delete {
+        /// Profile name
+        name: String,
+    },
+    /// Render a saved profile as configuration for a Linux display tool
+    Export {
+        /// Profile name
+        name: String,
+
+        /// Target tool
+        #[arg(long = "to", value_enum)]
+        format: ExportFormat,
+
+        /// Output name to use for a display, as UUID=NAME (e.g. UUID=DP-1); repeatable
+        #[arg(long = "output-name")]
+        output_names: Vec<String>,
+
+        /// Write to this file instead of printing
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Import Linux output configuration into a profile
+    Import {
+        /// Profile name to create or update
+        name: String,
+
+        /// Source format
+        #[arg(long = "from", value_enum)]
+        format: ImportFormat,
+
+        /// Configuration file to read
+        #[arg(short, long)]
+        file: PathBuf,
+
+        /// kanshi profile to import (defaults to the first one)
+        #[arg(long)]
+        kanshi_profile: Option<String>,
+
+        /// monitors.xml configuration to import, counting from 1 (defaults to the one
+        /// for the profile's monitors, then the first)
+        #[arg(long)]
+        gnome_configuration: Option<usize>,
+    },
+}
+
+impl Commands {
+    /// Whether the command changes the display configuration and so must hold the
+    /// reconfiguration lock; `watch` takes it itself each time it applies a profile
+    fn reconfigures(&self) -> bool {
+        match self {
+            Commands::SetMode { .. }
+            | Commands::SetRefresh { .. }
+            | Commands::Reset { .. }
+            | Commands::SetBrightness { .. }
+            | Commands::Arrange { .. }
+            | Commands::SetMain { .. }
+            | Commands::Rotate { .. }
+            | Commands::Underscan { .. }
+            | Commands::Overscan { .. }
+            | Commands::Hdr { .. } => true,
+            Commands::Apply { check, .. } => !check,
+            Commands::Mirror { plan, .. } => !plan,
+            Commands::Preset { command } => matches!(command, PresetCommands::Set { .. }),
+            Commands::Profile { command } => matches!(command, ProfileCommands::Apply { .. }),
+            _ => false,
+        }
+    }
 }
 
 fn main() {
     let cli = Cli::parse();
+    let wait = (!cli.no_wait).then(|| Duration::from_secs(cli.wait_timeout));
+    let retry = RetryPolicy {
+        retries: cli.retries,
+        delay: Duration::from_millis(cli.retry_delay_ms),
+    };
+
+    if cli.rescan && !matches!(cli.command, Commands::Rescan) {
+        rescan_before_command();
+    }
+
+    // Held until the command finishes; exiting releases it too
+    let _lock = if cli.command.reconfigures() {
+        match ReconfigureLock::acquire(wait) {
+            Ok(lock) => Some(lock),
+            Err(err) => {
+                eprintln!("Error: {}", err);
+                std::process::exit(1);
+            }
+        }
+    } else {
+        None
+    };
 
     match &cli.command {
-        Commands::List { verbose, display } => {
-            list_displays(*verbose, *display);
+        Commands::List {
+            verbose,
+            display,
+            kind,
+            layout,
+            json,
+        } => {
+            list_displays(*verbose, display.as_deref(), *kind, *layout, *json);
         }
         Commands::GetMode { display } => {
             get_display_mode(display);
         }
         Commands::SetMode { display, mode } => {
-            set_display_mode(display, *mode);
+            set_display_mode(display, *mode, cli.force, retry);
         }
+        Commands::SetRefresh { display, hz } => set_refresh_rate(display, *hz, cli.force, retry),
+        Commands::Reset {
+            display,
+            native,
+            brightness,
+            underscan,
+            rotation,
+        } => reset_display(
+            display,
+            ResetOptions {
+                native: *native,
+                brightness: *brightness,
+                underscan: *underscan,
+                rotation: *rotation,
+                force: cli.force,
+                retry,
+            },
+        ),
         Commands::GetBrightness { display } => {
-            get_brightness(*display);
+            get_brightness(display.as_deref());
         }
         Commands::SetBrightness {
             display,
             brightness,
         } => {
-            set_brightness(*display, *brightness);
+            set_brightness(display, *brightness);
         }
-        Commands::Watch {} => {
-            watch();
+        Commands::Watch {
+            auto_profile,
+            fallback,
+            settle_ms,
+        } => {
+            let options = auto_profile.then(|| AutoProfileOptions {
+                fallback: fallback.clone(),
+                settle: Duration::from_millis(*settle_ms),
+                force: cli.force,
+                wait,
+                retry,
+            });
+            watch(options);
+        }
+        Commands::Apply { file, check, json } => {
+            apply_desired_state(file, *check, *json, cli.force, retry);
+        }
+        Commands::Arrange {
+            display,
+            origin,
+            right_of,
+            left_of,
+            above,
+            below,
+            align,
+        } => {
+            let relative = [
+                (Placement::RightOf, right_of),
+                (Placement::LeftOf, left_of),
+                (Placement::Above, above),
+                (Placement::Below, below),
+            ]
+            .into_iter()
+            .find_map(|(placement, anchor)| anchor.clone().map(|anchor| (placement, anchor)));
+            let position = match (origin, relative) {
+                (Some(origin), _) => Position::Absolute(*origin),
+                (None, Some((placement, anchor))) => Position::Relative {
+                    placement,
+                    anchor,
+                    alignment: *align,
+                },
+                // The "position" argument group requires one of them
+                (None, None) => unreachable!(),
+            };
+            arrange(display, &position);
         }
+        Commands::SetMain { display } => set_main(display),
+        Commands::Rotate { display, degrees } => rotate(display, *degrees),
+        Commands::Underscan { display, value } => set_underscan(display, *value),
+        Commands::Overscan { display, state } => set_overscan(display, state.enabled()),
+        Commands::Hdr { display, state } => set_hdr(display, state.enabled()),
+        Commands::Mirror {
+            source,
+            targets,
+            best_mode,
+            plan,
+            off,
+        } => match (source, off) {
+            (_, Some(display)) => stop_mirroring(display.as_deref()),
+            (Some(source), None) if *plan => plan_mirror_displays(source, targets),
+            (Some(source), None) => mirror_displays(source, targets, *best_mode),
+            // clap requires --source unless --off is present
+            (None, None) => unreachable!(),
+        },
+        Commands::Preset { command } => match command {
+            PresetCommands::List { display } => preset_list(display),
+            PresetCommands::Get { display } => preset_get(display),
+            PresetCommands::Set { display, name } => preset_set(display, name),
+        },
+        Commands::Rescan => rescan(),
+        Commands::Layout { svg } => show_layout(svg.as_deref()),
+        Commands::Displayplacer { command } => match command {
+            DisplayplacerCommands::Import {
+                args,
+                output,
+                profile,
+            } => displayplacer_import(args, output.as_deref(), profile.as_deref()),
+            DisplayplacerCommands::Export { profile } => displayplacer_export(profile.as_deref()),
+        },
+        Commands::Profile { command } => match command {
+            ProfileCommands::Save { name } => profile_save(name),
+            ProfileCommands::Apply { name, closest } => {
+                profile_apply(name, *closest, cli.force, retry)
+            }
+            ProfileCommands::List => profile_list(),
+            ProfileCommands::Show { name } => profile_show(name),
+            ProfileCommands::Delete { name } => profile_delete(name),
+            ProfileCommands::Export {
+                name,
+                format,
+                output_names,
+                output,
+            } => profile_export(name, *format, output_names, output.as_deref()),
+            ProfileCommands::Import {
+                name,
+                format,
+                file,
+                kanshi_profile,
+                gnome_configuration,
+            } => profile_import(
+                name,
+                *format,
+                file,
+                kanshi_profile.as_deref(),
+                *gnome_configuration,
+            ),
+        },
     }
 }
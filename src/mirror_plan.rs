@@ -0,0 +1,403 @@
+// Mirror mode planning
+// Mirrored displays show the same picture, so they need modes with the same logical
+// resolution. This finds the resolutions every display in a prospective mirror set offers
+// and ranks them so the picture fits each panel well, working only on mode snapshots.
+
+use crate::mode_descriptor::{ModeDescriptor, ModeSnapshot};
+
+/// Weight of aspect-ratio mismatch, which letterboxes or stretches the picture
+const ASPECT_WEIGHT: f64 = 100.0;
+
+/// Weight of scaling away from a panel's native pixels, which blurs the picture
+const SCALING_WEIGHT: f64 = 10.0;
+
+/// Penalty when the displays cannot share one refresh rate
+const REFRESH_MISMATCH_PENALTY: f64 = 1.0;
+
+/// A display that would take part in a mirror set
+#[derive(Clone, Debug)]
+pub struct MirrorCandidate {
+    pub display_id: u32,
+    pub modes: Vec<ModeSnapshot>,
+}
+
+/// The mode a display would use for a shared resolution
+#[derive(Clone, Debug)]
+pub struct MirrorModeChoice {
+    pub display_id: u32,
+    pub mode: ModeSnapshot,
+}
+
+/// A shared resolution with the mode each display would use, best first when ranked
+#[derive(Clone, Debug)]
+pub struct MirrorPlan {
+    /// Logical width in points
+    pub width: i32,
+    /// Logical height in points
+    pub height: i32,
+    pub choices: Vec<MirrorModeChoice>,
+    /// Largest relative difference between this resolution's aspect ratio and a panel's
+    pub aspect_mismatch: f64,
+    /// Largest relative difference between a chosen mode's backing pixels and the panel's
+    pub scaling: f64,
+    /// Whether every chosen mode has the same refresh rate
+    pub same_refresh: bool,
+    /// Lowest refresh rate among the chosen modes
+    pub refresh_rate: f64,
+}
+
+impl MirrorPlan {
+    /// Lower is better
+    pub fn score(&self) -> f64 {
+        let mut score = self.aspect_mismatch * ASPECT_WEIGHT + self.scaling * SCALING_WEIGHT;
+        if !self.same_refresh {
+            score += REFRESH_MISMATCH_PENALTY;
+        }
+        // Among otherwise equal plans prefer faster refresh
+        score - self.refresh_rate / 1000.0
+    }
+}
+
+/// Native pixel size of a panel: its native mode, or its largest mode
+fn native_pixels(modes: &[ModeSnapshot]) -> Option<(i32, i32)> {
+    modes
+        .iter()
+        .find(|mode| mode.native)
+        .or_else(|| {
+            modes.iter().max_by_key(|mode| {
+                mode.descriptor.pixels_wide as i64 * mode.descriptor.pixels_high as i64
+            })
+        })
+        .map(|mode| (mode.descriptor.pixels_wide, mode.descriptor.pixels_high))
+}
+
+fn aspect(width: i32, height: i32) -> f64 {
+    width as f64 / height.max(1) as f64
+}
+
+/// How far a mode's backing pixels are from the panel's, relative to the panel
+fn scaling(mode: &ModeDescriptor, native: (i32, i32)) -> f64 {
+    (mode.pixels_wide - native.0).abs() as f64 / native.0.max(1) as f64
+}
+
+/// Pick the mode a display should use at a resolution, preferring `refresh_rate`, then
+/// backing pixels closest to the panel, then faster refresh
+fn choose_mode<'a>(
+    modes: &[&'a ModeSnapshot],
+    native: (i32, i32),
+    refresh_rate: Option<f64>,
+) -> Option<&'a ModeSnapshot> {
+    modes.iter().copied().min_by(|a, b| {
+        let matches_refresh = |mode: &ModeSnapshot| {
+            refresh_rate.is_some_and(|rate| (mode.descriptor.refresh_rate - rate).abs() < 0.01)
+        };
+        matches_refresh(b)
+            .cmp(&matches_refresh(a))
+            .then(scaling(&a.descriptor, native).total_cmp(&scaling(&b.descriptor, native)))
+            .then(
+                b.descriptor
+                    .refresh_rate
+                    .total_cmp(&a.descriptor.refresh_rate),
+            )
+            .then(a.mode_number.cmp(&b.mode_number))
+    })
+}
+
+/// Rank the resolutions every candidate offers, best first
+///
+/// Only user-visible modes are considered. For each shared resolution every display
+/// gets its best mode, aiming for one refresh rate across the set. Plans are ranked by
+/// how well the resolution's aspect ratio fits each panel, then how close the modes are
+/// to the panels' native pixels, then refresh rate.
+pub fn plan_mirror(candidates: &[MirrorCandidate]) -> Vec<MirrorPlan> {
+    if candidates.is_empty() {
+        return Vec::new();
+    }
+
+    let visible = |candidate: &MirrorCandidate| -> Vec<ModeSnapshot> {
+        candidate
+            .modes
+            .iter()
+            .filter(|mode| mode.user_visible)
+            .cloned()
+            .collect()
+    };
+    let modes: Vec<Vec<ModeSnapshot>> = candidates.iter().map(visible).collect();
+    let natives: Vec<Option<(i32, i32)>> = candidates
+        .iter()
+        .map(|candidate| native_pixels(&candidate.modes))
+        .collect();
+
+    let mut resolutions: Vec<(i32, i32)> = modes[0]
+        .iter()
+        .map(|mode| (mode.descriptor.width, mode.descriptor.height))
+        .filter(|size| {
+            modes[1..].iter().all(|other| {
+                other
+                    .iter()
+                    .any(|mode| (mode.descriptor.width, mode.descriptor.height) == *size)
+            })
+        })
+        .collect();
+    resolutions.sort_unstable();
+    resolutions.dedup();
+
+    let mut plans: Vec<MirrorPlan> = resolutions
+        .into_iter()
+        .filter_map(|(width, height)| {
+            let at_size: Vec<Vec<&ModeSnapshot>> = modes
+                .iter()
+                .map(|display_modes| {
+                    display_modes
+                        .iter()
+                        .filter(|mode| {
+                            mode.descriptor.width == width && mode.descriptor.height == height
+                        })
+                        .collect()
+                })
+                .collect();
+
+            // Aim for the fastest refresh rate that every display offers at this size
+            let mut shared_rates: Vec<f64> = at_size[0]
+                .iter()
+                .map(|mode| mode.descriptor.refresh_rate)
+                .filter(|rate| {
+                    at_size[1..].iter().all(|other| {
+                        other
+                            .iter()
+                            .any(|mode| (mode.descriptor.refresh_rate - rate).abs() < 0.01)
+                    })
+                })
+                .collect();
+            shared_rates.sort_by(|a, b| b.total_cmp(a));
+            let target_rate = shared_rates.first().copied();
+
+            let mut choices = Vec::with_capacity(candidates.len());
+            let mut aspect_mismatch: f64 = 0.0;
+            let mut worst_scaling: f64 = 0.0;
+            for ((candidate, display_modes), native) in
+                candidates.iter().zip(at_size.iter()).zip(natives.iter())
+            {
+                let native = native.unwrap_or((width, height));
+                let mode = choose_mode(display_modes, native, target_rate)?;
+                let panel_aspect = aspect(native.0, native.1);
+                aspect_mismatch = aspect_mismatch
+                    .max((aspect(width, height) - panel_aspect).abs() / panel_aspect);
+                worst_scaling = worst_scaling.max(scaling(&mode.descriptor, native));
+                choices.push(MirrorModeChoice {
+                    display_id: candidate.display_id,
+                    mode: mode.clone(),
+                });
+            }
+
+            let rates = choices
+                .iter()
+                .map(|choice| choice.mode.descriptor.refresh_rate);
+            let refresh_rate = rates.clone().fold(f64::INFINITY, f64::min);
+            let same_refresh = rates.clone().all(|rate| (rate - refresh_rate).abs() < 0.01);
+
+            Some(MirrorPlan {
+                width,
+                height,
+                choices,
+                aspect_mismatch,
+                scaling: worst_scaling,
+                same_refresh,
+                refresh_rate,
+            })
+        })
+        .collect();
+
+    plans.sort_by(|a, b| {
+        a.score()
+            .total_cmp(&b.score())
+            .then((b.width * b.height).cmp(&(a.width * a.height)))
+    });
+    plans
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A mode of `width` x `height` points backed by `pixels_wide` x `pixels_high` pixels
+    fn mode(
+        mode_number: i32,
+        (width, height): (i32, i32),
+        (pixels_wide, pixels_high): (i32, i32),
+        refresh_rate: f64,
+    ) -> ModeSnapshot {
+        ModeSnapshot {
+            mode_number,
+            descriptor: ModeDescriptor {
+                width,
+                height,
+                pixels_wide,
+                pixels_high,
+                scale: (pixels_wide / width) as f32,
+                refresh_rate,
+                hidpi: pixels_wide > width,
+                depth: Some(8),
+                hdr: false,
+            },
+            user_visible: true,
+            native: false,
+            interlaced: false,
+            stretched: false,
+        }
+    }
+
+    fn native(mut mode: ModeSnapshot) -> ModeSnapshot {
+        mode.native = true;
+        mode
+    }
+
+    fn hidden(mut mode: ModeSnapshot) -> ModeSnapshot {
+        mode.user_visible = false;
+        mode
+    }
+
+    fn candidate(display_id: u32, modes: Vec<ModeSnapshot>) -> MirrorCandidate {
+        MirrorCandidate { display_id, modes }
+    }
+
+    fn sizes(plans: &[MirrorPlan]) -> Vec<(i32, i32)> {
+        plans.iter().map(|plan| (plan.width, plan.height)).collect()
+    }
+
+    /// 16:10 laptop panel with 2880x1800 pixels
+    fn laptop() -> MirrorCandidate {
+        candidate(
+            1,
+            vec![
+                native(mode(1, (1440, 900), (2880, 1800), 60.0)),
+                mode(2, (1280, 800), (2560, 1600), 60.0),
+                mode(3, (1280, 720), (2560, 1440), 60.0),
+                mode(4, (1024, 768), (2048, 1536), 60.0),
+            ],
+        )
+    }
+
+    /// 16:9 projector with 1920x1080 pixels
+    fn projector() -> MirrorCandidate {
+        candidate(
+            2,
+            vec![
+                native(mode(10, (1920, 1080), (1920, 1080), 60.0)),
+                mode(11, (1280, 800), (1280, 800), 60.0),
+                mode(12, (1280, 720), (1280, 720), 60.0),
+                mode(13, (1024, 768), (1024, 768), 60.0),
+            ],
+        )
+    }
+
+    #[test]
+    fn plans_cover_resolutions_every_display_offers() {
+        let plans = plan_mirror(&[laptop(), projector()]);
+        let mut shared = sizes(&plans);
+        shared.sort_unstable();
+        assert_eq!(shared, vec![(1024, 768), (1280, 720), (1280, 800)]);
+        for plan in plans.iter() {
+            let ids: Vec<u32> = plan
+                .choices
+                .iter()
+                .map(|choice| choice.display_id)
+                .collect();
+            assert_eq!(ids, vec![1, 2]);
+            assert!(plan.choices.iter().all(|choice| {
+                (choice.mode.descriptor.width, choice.mode.descriptor.height)
+                    == (plan.width, plan.height)
+            }));
+        }
+    }
+
+    #[test]
+    fn hidden_modes_are_not_shared() {
+        let mut laptop = laptop();
+        laptop.modes = laptop.modes.into_iter().map(hidden).collect();
+        laptop
+            .modes
+            .push(native(mode(1, (1440, 900), (2880, 1800), 60.0)));
+        let mut projector = projector();
+        projector
+            .modes
+            .push(mode(14, (1440, 900), (1440, 900), 60.0));
+
+        assert_eq!(sizes(&plan_mirror(&[laptop, projector])), vec![(1440, 900)]);
+    }
+
+    #[test]
+    fn plans_are_ranked_by_aspect_ratio_fit() {
+        // A 16:10 picture letterboxes less on the 16:9 projector than a 16:9 picture does
+        // on the 16:10 laptop, and 4:3 fits neither
+        let plans = plan_mirror(&[laptop(), projector()]);
+        assert_eq!(sizes(&plans), vec![(1280, 800), (1280, 720), (1024, 768)]);
+        assert!(plans[0].aspect_mismatch < plans[1].aspect_mismatch);
+        assert!(plans[1].aspect_mismatch < plans[2].aspect_mismatch);
+        assert!(plans[0].score() < plans[1].score());
+    }
+
+    #[test]
+    fn displays_share_a_refresh_rate_when_they_can() {
+        let first = candidate(
+            1,
+            vec![
+                native(mode(1, (1920, 1080), (1920, 1080), 50.0)),
+                mode(2, (1920, 1080), (1920, 1080), 60.0),
+            ],
+        );
+        let second = candidate(
+            2,
+            vec![
+                native(mode(3, (1920, 1080), (1920, 1080), 75.0)),
+                mode(4, (1920, 1080), (1920, 1080), 60.0),
+            ],
+        );
+
+        let plans = plan_mirror(&[first, second]);
+        assert_eq!(plans.len(), 1);
+        assert!(plans[0].same_refresh);
+        assert_eq!(plans[0].refresh_rate, 60.0);
+        let numbers: Vec<i32> = plans[0]
+            .choices
+            .iter()
+            .map(|choice| choice.mode.mode_number)
+            .collect();
+        assert_eq!(numbers, vec![2, 4]);
+    }
+
+    #[test]
+    fn refresh_rates_may_differ_when_none_is_shared() {
+        let first = candidate(1, vec![native(mode(1, (1920, 1080), (1920, 1080), 60.0))]);
+        let second = candidate(2, vec![native(mode(2, (1920, 1080), (1920, 1080), 50.0))]);
+        let shared = candidate(3, vec![native(mode(3, (1920, 1080), (1920, 1080), 60.0))]);
+
+        let plans = plan_mirror(&[first.clone(), second]);
+        assert_eq!(plans.len(), 1);
+        assert!(!plans[0].same_refresh);
+        assert_eq!(plans[0].refresh_rate, 50.0);
+
+        let matching = plan_mirror(&[first, shared]);
+        assert!(matching[0].same_refresh);
+        assert!(matching[0].score() < plans[0].score());
+    }
+
+    #[test]
+    fn no_common_resolution_gives_no_plans() {
+        let first = candidate(1, vec![native(mode(1, (1920, 1080), (1920, 1080), 60.0))]);
+        let second = candidate(2, vec![native(mode(2, (1280, 800), (2560, 1600), 60.0))]);
+        assert!(plan_mirror(&[first, second]).is_empty());
+        assert!(plan_mirror(&[]).is_empty());
+    }
+
+    #[test]
+    fn single_candidate_ranks_its_own_modes() {
+        let plans = plan_mirror(&[laptop()]);
+        assert_eq!(plans.len(), 4);
+        assert!(plans.iter().all(|plan| plan.choices.len() == 1));
+        // The native mode fits its own panel exactly
+        assert_eq!(sizes(&plans)[0], (1440, 900));
+        assert_eq!(plans[0].aspect_mismatch, 0.0);
+        assert_eq!(plans[0].scaling, 0.0);
+    }
+}
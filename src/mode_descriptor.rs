@@ -0,0 +1,334 @@
+// Stable description of a display mode
+// Mode numbers are assigned by the system and can change across reboots, OS updates
+// and docking, so stored configurations refer to a mode by its properties and
+// resolve it back to the current mode number when applied.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+use crate::monitor_panel::{MPDisplay, MPDisplayMode};
+
+/// Refresh rates closer than this are considered equal (59.94 vs 59.9401)
+const REFRESH_TOLERANCE: f64 = 0.01;
+
+/// Scale factors closer than this are considered equal
+const SCALE_TOLERANCE: f32 = 0.01;
+
+/// Bits per color channel of HDR modes
+const HDR_BITS_PER_SAMPLE: i32 = 10;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ModeDescriptor {
+    /// Logical width in points
+    pub width: i32,
+    /// Logical height in points
+    pub height: i32,
+    /// Backing store width in pixels
+    pub pixels_wide: i32,
+    /// Backing store height in pixels
+    pub pixels_high: i32,
+    /// Backing scale factor (2.0 for HiDPI modes)
+    pub scale: f32,
+    /// Precise refresh rate in Hz
+    pub refresh_rate: f64,
+    /// Whether the mode is a HiDPI (scaled) mode
+    pub hidpi: bool,
+    /// Color depth as reported by the mode description; matches any depth when absent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub depth: Option<i32>,
+    /// Whether the mode is an HDR (10-bit) mode on a display that supports HDR; not used
+    /// for matching since depth already tells HDR and SDR variants apart
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub hdr: bool,
+}
+
+/// A mode offered by a display, captured so it can be inspected without MonitorPanel
+#[derive(Clone, Debug)]
+pub struct ModeSnapshot {
+    pub mode_number: i32,
+    pub descriptor: ModeDescriptor,
+    pub user_visible: bool,
+    /// Whether this is the panel's native mode
+    pub native: bool,
+    pub interlaced: bool,
+    /// Whether the picture is stretched to fill the display
+    pub stretched: bool,
+}
+
+/// Whether a mode counts as HDR
+///
+/// MonitorPanel has no per-mode HDR flag. HDR modes are the 10-bit-per-channel ones, but
+/// displays without HDR offer 10-bit SDR modes as well, so those never count.
+pub fn is_hdr_mode(bits_per_sample: Option<i32>, display_has_hdr: bool) -> bool {
+    display_has_hdr && bits_per_sample.is_some_and(|bits| bits >= HDR_BITS_PER_SAMPLE)
+}
+
+impl ModeDescriptor {
+    /// Describe a mode offered by `display`
+    pub unsafe fn from_mode(display: &MPDisplay, mode: &MPDisplayMode) -> Self {
+        unsafe {
+            ModeDescriptor {
+                width: mode.width(),
+                height: mode.height(),
+                pixels_wide: mode.pixels_wide(),
+                pixels_high: mode.pixels_high(),
+                scale: mode.scale(),
+                refresh_rate: mode
+                    .scan_rate()
+                    .unwrap_or_else(|| mode.refresh_rate() as f64),
+                hidpi: mode.is_hidpi(),
+                depth: mode.depth(),
+                hdr: is_hdr_mode(mode.bits_per_sample(), display.has_hdr_modes()),
+            }
+        }
+    }
+
+    /// Whether `other` describes the same mode, tolerating float noise in refresh and scale
+    pub fn matches(&self, other: &ModeDescriptor) -> bool {
+        self.width == other.width
+            && self.height == other.height
+            && self.pixels_wide == other.pixels_wide
+            && self.pixels_high == other.pixels_high
+            && self.hidpi == other.hidpi
+            && (self.scale - other.scale).abs() < SCALE_TOLERANCE
+            && (self.refresh_rate - other.refresh_rate).abs() < REFRESH_TOLERANCE
+            && match (self.depth, other.depth) {
+                (Some(a), Some(b)) => a == b,
+                _ => true,
+            }
+    }
+
+    /// How far `other` is from this descriptor; lower is closer, 0.0 is a match
+    ///
+    /// Differences are weighted so that the HiDPI flag matters most, then logical size,
+    /// then backing pixels, refresh rate, scale and depth.
+    pub fn distance(&self, other: &ModeDescriptor) -> f64 {
+        if self.matches(other) {
+            return 0.0;
+        }
+
+        let mut distance = 0.0;
+        if self.hidpi != other.hidpi {
+            distance += 10_000.0;
+        }
+        distance += ((self.width - other.width).abs() + (self.height - other.height).abs()) as f64;
+        distance += ((self.pixels_wide - other.pixels_wide).abs()
+            + (self.pixels_high - other.pixels_high).abs()) as f64
+            * 0.5;
+        distance += (self.refresh_rate - other.refresh_rate).abs() * 10.0;
+        distance += (self.scale - other.scale).abs() as f64 * 100.0;
+        if let (Some(a), Some(b)) = (self.depth, other.depth)
+            && a != b
+        {
+            distance += 50.0;
+        }
+        distance
+    }
+
+    /// Find the mode number matching this descriptor among `modes`
+    ///
+    /// With `closest`, the nearest mode is used when there is no exact match, preferring
+    /// user-visible modes on ties. Otherwise a missing exact match is an error that names
+    /// the closest candidate.
+    pub fn find_in(&self, modes: &[ModeSnapshot], closest: bool) -> Result<i32, Error> {
+        if let Some(mode) = modes.iter().find(|mode| self.matches(&mode.descriptor)) {
+            return Ok(mode.mode_number);
+        }
+
+        let nearest = modes.iter().min_by(|a, b| {
+            self.distance(&a.descriptor)
+                .total_cmp(&self.distance(&b.descriptor))
+                .then(b.user_visible.cmp(&a.user_visible))
+                .then(a.mode_number.cmp(&b.mode_number))
+        });
+
+        match nearest {
+            Some(mode) if closest => Ok(mode.mode_number),
+            Some(mode) => Err(Error::NotFound(format!(
+                "No mode exactly matches {}; closest is mode #{} ({})",
+                self, mode.mode_number, mode.descriptor
+            ))),
+            None => Err(Error::NotFound(format!(
+                "No modes available to match {}",
+                self
+            ))),
+        }
+    }
+
+    /// Whether `other` has the same logical size, backing pixels and scale, ignoring
+    /// refresh rate
+    pub fn same_resolution(&self, other: &ModeDescriptor) -> bool {
+        self.width == other.width
+            && self.height == other.height
+            && self.pixels_wide == other.pixels_wide
+            && self.pixels_high == other.pixels_high
+            && self.hidpi == other.hidpi
+            && (self.scale - other.scale).abs() < SCALE_TOLERANCE
+    }
+
+    /// Find the mode with this descriptor's resolution and scale at `refresh_rate`
+    ///
+    /// Like MonitorPanel's `modeMatchingResolutionOfMode:withScanRate:`, but tolerating
+    /// float noise in the rate. Among matches, modes with the same depth and HDR flag as
+    /// this one win, then user-visible modes. A missing rate is an error that lists the
+    /// rates available at this resolution.
+    pub fn find_refresh_rate(
+        &self,
+        modes: &[ModeSnapshot],
+        refresh_rate: f64,
+    ) -> Result<i32, Error> {
+        let variants: Vec<&ModeSnapshot> = modes
+            .iter()
+            .filter(|mode| self.same_resolution(&mode.descriptor))
+            .collect();
+
+        let chosen = variants
+            .iter()
+            .filter(|mode| (mode.descriptor.refresh_rate - refresh_rate).abs() < REFRESH_TOLERANCE)
+            .min_by_key(|mode| {
+                (
+                    mode.descriptor.depth != self.depth,
+                    mode.descriptor.hdr != self.hdr,
+                    !mode.user_visible,
+                    mode.mode_number,
+                )
+            });
+        if let Some(mode) = chosen {
+            return Ok(mode.mode_number);
+        }
+
+        let mut rates: Vec<f64> = variants
+            .iter()
+            .map(|mode| mode.descriptor.refresh_rate)
+            .collect();
+        rates.sort_by(|a, b| a.total_cmp(b));
+        rates.dedup_by(|a, b| (*a - *b).abs() < REFRESH_TOLERANCE);
+        let rates: Vec<String> = rates
+            .into_iter()
+            .map(|rate| format!("{}Hz", format_refresh_rate(rate)))
+            .collect();
+        Err(Error::NotFound(format!(
+            "No {}x{} mode at {}Hz; available rates: {}",
+            self.width,
+            self.height,
+            format_refresh_rate(refresh_rate),
+            if rates.is_empty() {
+                "none".to_string()
+            } else {
+                rates.join(", ")
+            }
+        )))
+    }
+
+    /// Resolve this descriptor to the current mode number on `display`
+    pub unsafe fn resolve(&self, display: &MPDisplay, closest: bool) -> Result<i32, Error> {
+        unsafe { self.find_in(&mode_snapshots(display), closest) }
+    }
+}
+
+impl ModeSnapshot {
+    pub unsafe fn from_mode(display: &MPDisplay, mode: &MPDisplayMode) -> Self {
+        unsafe {
+            ModeSnapshot {
+                mode_number: mode.mode_number(),
+                descriptor: ModeDescriptor::from_mode(display, mode),
+                user_visible: mode.is_user_visible(),
+                native: mode.is_native_mode(),
+                interlaced: mode.is_interlaced(),
+                stretched: mode.is_stretched(),
+            }
+        }
+    }
+}
+
+/// Capture all modes offered by a display
+pub unsafe fn mode_snapshots(display: &MPDisplay) -> Vec<ModeSnapshot> {
+    unsafe {
+        display
+            .all_modes()
+            .map(|modes| {
+                modes
+                    .iter()
+                    .map(|mode| ModeSnapshot::from_mode(display, mode))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// Format a refresh rate without trailing zeros for whole numbers (60, 59.94)
+pub fn format_refresh_rate(rate: f64) -> String {
+    if (rate - rate.round()).abs() < REFRESH_TOLERANCE {
+        format!("{}", rate.round())
+    } else {
+        format!("{:.2}", rate)
+    }
+}
+
+impl fmt::Display for ModeDescriptor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}x{}", self.width, self.height)?;
+        if self.pixels_wide != self.width || self.pixels_high != self.height {
+            write!(f, " ({}x{} pixels)", self.pixels_wide, self.pixels_high)?;
+        }
+        write!(f, " @ {}Hz", format_refresh_rate(self.refresh_rate))?;
+        if self.scale != 1.0 {
+            write!(f, " scale={:.1}x", self.scale)?;
+        }
+        if let Some(depth) = self.depth {
+            write!(f, " depth={}", depth)?;
+        }
+        if self.hidpi {
+            write!(f, " [HiDPI]")?;
+        }
+        if self.hdr {
+            write!(f, " [HDR]")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A 4K mode with `bits_per_sample` bits per channel, as described on a display that
+    /// does or does not support HDR
+    fn uhd(bits_per_sample: i32, display_has_hdr: bool) -> ModeDescriptor {
+        ModeDescriptor {
+            width: 3840,
+            height: 2160,
+            pixels_wide: 3840,
+            pixels_high: 2160,
+            scale: 1.0,
+            refresh_rate: 60.0,
+            hidpi: false,
+            depth: None,
+            hdr: is_hdr_mode(Some(bits_per_sample), display_has_hdr),
+        }
+    }
+
+    #[test]
+    fn ten_bit_mode_on_hdr_display_is_hdr() {
+        let mode = uhd(10, true);
+        assert!(mode.hdr);
+        assert!(mode.to_string().ends_with(" [HDR]"));
+    }
+
+    #[test]
+    fn ten_bit_mode_on_sdr_display_is_not_hdr() {
+        let mode = uhd(10, false);
+        assert!(!mode.hdr);
+        assert!(!mode.to_string().contains("HDR"));
+        let json = serde_json::to_string(&mode).unwrap();
+        assert!(!json.contains("hdr"), "{}", json);
+    }
+
+    #[test]
+    fn eight_bit_mode_is_never_hdr() {
+        assert!(!uhd(8, true).hdr);
+        assert!(!is_hdr_mode(None, true));
+    }
+}
@@ -0,0 +1,81 @@
+// Mode change safety
+// A mode the display cannot show leaves it blank, which is costly on a machine reached
+// remotely. Mode changes are refused while macOS runs in safe mode, where display drivers
+// are limited, and modes hidden from System Settings, interlaced or stretched are
+// refused unless forced.
+
+use crate::error::Error;
+use crate::mode_descriptor::{ModeSnapshot, mode_snapshots};
+use crate::monitor_panel::{MPDisplay, MPDisplayMgr};
+
+/// Check that switching to `mode` is safe, or that the change is forced
+pub fn check_mode_safety(mode: &ModeSnapshot, safe_mode: bool, force: bool) -> Result<(), Error> {
+    if force {
+        return Ok(());
+    }
+
+    if safe_mode {
+        return Err(Error::Invalid(
+            "macOS is running in safe mode, where display drivers are limited and a new \
+             mode may leave the display blank; pass --force to change modes anyway"
+                .to_string(),
+        ));
+    }
+
+    let mut reasons = Vec::new();
+    if !mode.user_visible {
+        reasons.push("hidden from System Settings");
+    }
+    if mode.interlaced {
+        reasons.push("interlaced");
+    }
+    if mode.stretched {
+        reasons.push("stretched to fill the display");
+    }
+    if reasons.is_empty() {
+        return Ok(());
+    }
+
+    Err(Error::Invalid(format!(
+        "Refusing mode #{} ({}) because it is {}; such modes may not display on every \
+         monitor. Pass --force to use it anyway",
+        mode.mode_number,
+        mode.descriptor,
+        reasons.join(", ")
+    )))
+}
+
+/// Whether macOS is running in safe mode
+pub unsafe fn running_in_safe_mode() -> bool {
+    unsafe {
+        MPDisplayMgr::shared()
+            .or_else(|| MPDisplayMgr::new())
+            .is_some_and(|mgr| mgr.running_in_safe_mode())
+    }
+}
+
+/// Check that `display` can safely switch to `mode_number`, or that the change is forced
+pub unsafe fn check_mode_change(
+    mp_display: &MPDisplay,
+    mode_number: i32,
+    force: bool,
+) -> Result<(), Error> {
+    if force {
+        return Ok(());
+    }
+
+    unsafe {
+        let modes = mode_snapshots(mp_display);
+        let mode = modes
+            .iter()
+            .find(|mode| mode.mode_number == mode_number)
+            .ok_or_else(|| {
+                Error::NotFound(format!(
+                    "Mode #{} not found for display ID {}",
+                    mode_number,
+                    mp_display.display_id()
+                ))
+            })?;
+        check_mode_safety(mode, running_in_safe_mode(), force)
+    }
+}
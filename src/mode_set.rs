@@ -0,0 +1,132 @@
+// Mode switching
+// MonitorPanel's setModeNumber: passes on the CGError from Core Graphics. Some of those
+// errors are transient, such as while a display wakes from sleep or the window server is
+// still reconfiguring after a hot plug, so those are retried with exponential backoff.
+
+use std::thread;
+use std::time::Duration;
+
+use crate::error::Error;
+use crate::monitor_panel::MPDisplay;
+
+/// Retries of a transient mode change failure unless `--retries` says otherwise
+pub const DEFAULT_RETRIES: u32 = 3;
+/// First retry delay in milliseconds unless `--retry-delay-ms` says otherwise
+pub const DEFAULT_RETRY_DELAY_MS: u64 = 250;
+/// Most retries `--retries` accepts
+pub const MAX_RETRIES: u32 = 10;
+/// Longest delay between retries in milliseconds; backoff stops doubling here
+pub const MAX_RETRY_DELAY_MS: u64 = 10_000;
+
+/// How often and how long to retry a mode change that failed with a transient error
+#[derive(Clone, Copy, Debug)]
+pub struct RetryPolicy {
+    /// Attempts after the first one
+    pub retries: u32,
+    /// Delay before the first retry; doubled before each later one, up to
+    /// `MAX_RETRY_DELAY_MS`
+    pub delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            retries: DEFAULT_RETRIES,
+            delay: Duration::from_millis(DEFAULT_RETRY_DELAY_MS),
+        }
+    }
+}
+
+/// A CGError value returned by a mode change or display configuration call
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CgError(pub i32);
+
+impl CgError {
+    /// The CGError constant name, if the code is a known one
+    pub fn name(self) -> Option<&'static str> {
+        let name = match self.0 {
+            0 => "kCGErrorSuccess",
+            1000 => "kCGErrorFailure",
+            1001 => "kCGErrorIllegalArgument",
+            1002 => "kCGErrorInvalidConnection",
+            1003 => "kCGErrorInvalidContext",
+            1004 => "kCGErrorCannotComplete",
+            1006 => "kCGErrorNotImplemented",
+            1007 => "kCGErrorRangeCheck",
+            1008 => "kCGErrorTypeCheck",
+            1010 => "kCGErrorInvalidOperation",
+            1011 => "kCGErrorNoneAvailable",
+            _ => return None,
+        };
+        Some(name)
+    }
+
+    /// What the error usually means when changing a display
+    pub fn explanation(self) -> &'static str {
+        match self.0 {
+            0 => "the change was made",
+            1000 => "the window server refused the change",
+            1001 => "the display does not accept this value, such as a mode it does not offer",
+            1002 => "the connection to the window server is invalid; is a user logged in?",
+            1003 => "the display configuration context is invalid",
+            1004 => "the display is asleep or being reconfigured",
+            1006 => "the display driver does not support this change",
+            1007 => "a value is out of range for this display",
+            1008 => "a value has the wrong type for this display",
+            1010 => "the display cannot be changed right now, for example while mirrored",
+            1011 => "the display is not available, for example while it is being connected",
+            _ => "unknown result code",
+        }
+    }
+
+    /// Whether retrying the same mode change later may succeed
+    pub fn is_transient(self) -> bool {
+        matches!(self.0, 1004 | 1011)
+    }
+}
+
+impl std::fmt::Display for CgError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.name() {
+            Some(name) => write!(f, "{} ({}): {}", name, self.0, self.explanation()),
+            None => write!(f, "error code {}: {}", self.0, self.explanation()),
+        }
+    }
+}
+
+/// Switch `mp_display` to `mode_number`, retrying transient failures per `policy`
+///
+/// The returned error names the last CGError and how many attempts were made; callers
+/// add which display and mode it was for.
+pub unsafe fn set_mode(
+    mp_display: &MPDisplay,
+    mode_number: i32,
+    policy: RetryPolicy,
+) -> Result<(), Error> {
+    let max_delay = Duration::from_millis(MAX_RETRY_DELAY_MS);
+    let mut delay = policy.delay.min(max_delay);
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+        let error = CgError(unsafe { mp_display.set_mode_number(mode_number) });
+        if error.0 == 0 {
+            return Ok(());
+        }
+        if !error.is_transient() || attempt > policy.retries {
+            let attempts = if attempt > 1 {
+                format!(" after {} attempts", attempt)
+            } else {
+                String::new()
+            };
+            return Err(Error::Platform(format!("{}{}", error, attempts)));
+        }
+        eprintln!(
+            "Mode #{} not set yet ({}), retrying in {}ms",
+            mode_number,
+            error,
+            delay.as_millis()
+        );
+        thread::sleep(delay);
+        delay = delay.saturating_mul(2).min(max_delay);
+    }
+}
@@ -8,4 +8,4 @@ pub use monitor_panel::MPDisplayMgr;
 
 // These types are used internally but can be accessed via monitor_panel:: if needed
 #[allow(unused_imports)]
-pub use monitor_panel::{MPDisplay, MPDisplayMode};
+pub use monitor_panel::{MPDisplay, MPDisplayMode, MPDisplayPreset};
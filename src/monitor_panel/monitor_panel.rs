@@ -1,6 +1,7 @@
 // This provides access to detailed display mode information not available via Core Graphics
 
 use core_graphics::display::CGDirectDisplayID;
+use core_graphics::geometry::CGRect;
 use objc::runtime::{Class, Object};
 use objc::{msg_send, sel, sel_impl};
 
@@ -17,6 +18,70 @@ pub struct MPDisplayMode {
     obj: *mut Object,
 }
 
+pub struct MPDisplayPreset {
+    obj: *mut Object,
+}
+
+/// Mirror of `struct _CGSDisplayModeDescription` from CDStructures.h
+/// All fields are declared to keep the layout, even though only some are read.
+#[repr(C)]
+#[allow(dead_code)]
+struct CGSDisplayModeDescription {
+    display_mode_number: i32,
+    flags: i32,
+    width: i32,
+    height: i32,
+    depth: i32,
+    row_bytes: i32,
+    bits_per_pixel: i32,
+    bits_per_sample: i32,
+    samples_per_pixel: i32,
+    refresh_rate: i32,
+    horizontal_resolution: i32,
+    vertical_resolution: i32,
+    encoding: [std::os::raw::c_char; 129],
+    version: i32,
+    length: i32,
+    fix_pt_refresh_rate: i32,
+    io_mode_info_flags: i32,
+    io_display_mode_number: i32,
+    pixels_wide: i32,
+    pixels_high: i32,
+    resolution: f32,
+}
+
+/// Collect the MPDisplay objects in an NSArray
+unsafe fn display_array(array: *mut Object) -> Option<Vec<MPDisplay>> {
+    if array.is_null() {
+        return None;
+    }
+
+    let count: usize = unsafe { msg_send![array, count] };
+    let mut displays = Vec::with_capacity(count);
+
+    for i in 0..count {
+        let display_obj: *mut Object = unsafe { msg_send![array, objectAtIndex: i] };
+        if !display_obj.is_null() {
+            displays.push(MPDisplay { obj: display_obj });
+        }
+    }
+
+    Some(displays)
+}
+
+/// Copy an NSString into a Rust string
+unsafe fn ns_string(string: *mut Object) -> Option<String> {
+    if string.is_null() {
+        return None;
+    }
+    let cstr: *const i8 = unsafe { msg_send![string, UTF8String] };
+    if cstr.is_null() {
+        return None;
+    }
+    let c_str = unsafe { std::ffi::CStr::from_ptr(cstr) };
+    Some(c_str.to_string_lossy().into_owned())
+}
+
 impl MPDisplayMgr {
     /// Create a new MPDisplayMgr instance
     pub unsafe fn new() -> Option<Self> {
@@ -30,7 +95,8 @@ impl MPDisplayMgr {
         }
     }
 
-    /// Get the shared MPDisplayMgr instance
+    /// Get the shared MPDisplayMgr instance, which keeps its display list (and any
+    /// refresh of it) for the life of the process
     pub unsafe fn shared() -> Option<Self> {
         let cls = Class::get("MPDisplayMgr")?;
         let obj: *mut Object = msg_send![cls, sharedMgr];
@@ -44,21 +110,7 @@ impl MPDisplayMgr {
     /// Get all displays
     pub unsafe fn displays(&self) -> Option<Vec<MPDisplay>> {
         let array: *mut Object = msg_send![self.obj, displays];
-        if array.is_null() {
-            return None;
-        }
-
-        let count: usize = msg_send![array, count];
-        let mut displays = Vec::with_capacity(count);
-
-        for i in 0..count {
-            let display_obj: *mut Object = msg_send![array, objectAtIndex: i];
-            if !display_obj.is_null() {
-                displays.push(MPDisplay { obj: display_obj });
-            }
-        }
-
-        Some(displays)
+        unsafe { display_array(array) }
     }
 
     /// Get display with specific ID
@@ -70,6 +122,94 @@ impl MPDisplayMgr {
             Some(MPDisplay { obj })
         }
     }
+
+    /// Mirror displays together; the first display is the mirror master whose picture the
+    /// others show
+    pub unsafe fn create_mirror_set(&self, displays: &[&MPDisplay]) -> Option<()> {
+        let objects: Vec<*mut Object> = displays.iter().map(|display| display.obj).collect();
+        let cls = Class::get("NSArray")?;
+        let array: *mut Object =
+            msg_send![cls, arrayWithObjects: objects.as_ptr() count: objects.len()];
+        if array.is_null() {
+            return None;
+        }
+        let _: () = msg_send![self.obj, createMirrorSet: array];
+        Some(())
+    }
+
+    /// Make a display the master of its mirror set, optionally choosing the best mode
+    pub unsafe fn set_mirror_master(&self, display: &MPDisplay, use_best_mode: bool) {
+        let _: () = msg_send![self.obj, setMirrorMaster: display.obj useBestMode: use_best_mode];
+    }
+
+    /// Mirror every display to one display
+    pub unsafe fn mirror_all_displays_to(&self, display: &MPDisplay, use_best_mode: bool) {
+        let _: () =
+            msg_send![self.obj, mirrorAllDisplaysTo: display.obj useBestMode: use_best_mode];
+    }
+
+    /// Stop mirroring a display
+    pub unsafe fn stop_mirroring_for_display(&self, display: &MPDisplay) {
+        let _: () = msg_send![self.obj, stopMirroringForDisplay: display.obj];
+    }
+
+    /// Stop all mirroring
+    pub unsafe fn stop_all_mirroring(&self) {
+        let _: () = msg_send![self.obj, stopAllMirroring];
+    }
+
+    /// Check if any display is mirrored
+    pub unsafe fn is_any_display_mirrored(&self) -> bool {
+        let result: bool = msg_send![self.obj, isAnyDisplayMirrored];
+        result
+    }
+
+    /// Take the manager's access lock if it is free; returns whether it was taken
+    ///
+    /// The lock belongs to this process and must be released on the thread that took it;
+    /// it does not exclude other processes.
+    pub unsafe fn try_lock_access(&self) -> bool {
+        let result: bool = msg_send![self.obj, tryLockAccess];
+        result
+    }
+
+    /// Release the access lock taken by `try_lock_access`
+    pub unsafe fn unlock_access(&self) {
+        let _: () = msg_send![self.obj, unlockAccess];
+    }
+
+    /// Whether macOS was started in safe mode
+    pub unsafe fn running_in_safe_mode(&self) -> bool {
+        let result: bool = msg_send![self.obj, runningInSafeMode];
+        result
+    }
+
+    /// Re-read the display configuration from the window server
+    pub unsafe fn refresh_displays(&self) {
+        let _: () = msg_send![self.obj, refreshDisplays];
+    }
+
+    /// Rebuild the list of displays
+    pub unsafe fn update_displays_list(&self) {
+        let _: () = msg_send![self.obj, updateDisplaysList];
+    }
+
+    /// Get the displays in the same mirror set as a display, including it
+    pub unsafe fn mirror_set_for_display(&self, display: &MPDisplay) -> Option<Vec<MPDisplay>> {
+        let array: *mut Object = msg_send![self.obj, mirrorSetForDisplay: display.obj];
+        unsafe { display_array(array) }
+    }
+
+    /// Get the display with a specific persistent UUID (case-insensitive)
+    pub unsafe fn display_with_uuid(&self, uuid: &str) -> Option<MPDisplay> {
+        unsafe {
+            self.displays()?.into_iter().find(|display| {
+                display
+                    .uuid()
+                    .is_some_and(|display_uuid| display_uuid.eq_ignore_ascii_case(uuid))
+            })
+        }
+    }
 }
 
 impl MPDisplay {
@@ -93,6 +233,39 @@ impl MPDisplay {
         Some(modes)
     }
 
+    /// Get the modes with the same resolution as `mode`, one per refresh rate variant
+    pub unsafe fn modes_matching_resolution_of_mode(
+        &self,
+        mode: &MPDisplayMode,
+    ) -> Option<Vec<MPDisplayMode>> {
+        let array: *mut Object = msg_send![self.obj, modesMatchingResolutionOfMode: mode.obj];
+        if array.is_null() {
+            return None;
+        }
+
+        let count: usize = msg_send![array, count];
+        let mut modes = Vec::with_capacity(count);
+
+        for i in 0..count {
+            let mode_obj: *mut Object = msg_send![array, objectAtIndex: i];
+            if !mode_obj.is_null() {
+                modes.push(MPDisplayMode { obj: mode_obj });
+            }
+        }
+
+        Some(modes)
+    }
+
+    /// Re-read the modes the display offers
+    pub unsafe fn refresh_modes(&self) {
+        let _: () = msg_send![self.obj, refreshModes];
+    }
+
+    /// Rebuild the resolution list shown in System Settings from the modes
+    pub unsafe fn refresh_resolutions(&self) {
+        let _: () = msg_send![self.obj, refreshResolutions];
+    }
+
     /// Get the display ID
     pub unsafe fn display_id(&self) -> i32 {
         msg_send![self.obj, displayID]
@@ -112,6 +285,11 @@ impl MPDisplay {
         Some(c_str.to_string_lossy().into_owned())
     }
 
+    /// Get the name shown in System Settings
+    pub unsafe fn title_name(&self) -> Option<String> {
+        unsafe { ns_string(msg_send![self.obj, titleName]) }
+    }
+
     /// Check if display is HiDPI
     pub unsafe fn is_hidpi(&self) -> bool {
         let result: bool = msg_send![self.obj, isHiDPI];
@@ -124,6 +302,54 @@ impl MPDisplay {
         result
     }
 
+    /// Check if display is built in
+    pub unsafe fn is_built_in(&self) -> bool {
+        let result: bool = msg_send![self.obj, isBuiltIn];
+        result
+    }
+
+    /// Check if display is a built-in Retina panel
+    pub unsafe fn is_built_in_retina(&self) -> bool {
+        let result: bool = msg_send![self.obj, isBuiltInRetina];
+        result
+    }
+
+    /// Check if display is a TV
+    pub unsafe fn is_tv(&self) -> bool {
+        let result: bool = msg_send![self.obj, isTV];
+        result
+    }
+
+    /// Check if display is a projector
+    pub unsafe fn is_projector(&self) -> bool {
+        let result: bool = msg_send![self.obj, isProjector];
+        result
+    }
+
+    /// Check if display is an AirPlay display
+    pub unsafe fn is_airplay_display(&self) -> bool {
+        let result: bool = msg_send![self.obj, isAirPlayDisplay];
+        result
+    }
+
+    /// Check if display is an iPad used with Sidecar
+    pub unsafe fn is_sidecar_display(&self) -> bool {
+        let result: bool = msg_send![self.obj, isSidecarDisplay];
+        result
+    }
+
+    /// Check if display is an Apple Pro Display XDR
+    pub unsafe fn is_apple_pro_display(&self) -> bool {
+        let result: bool = msg_send![self.obj, isAppleProDisplay];
+        result
+    }
+
+    /// Check if display is 4K
+    pub unsafe fn is_4k(&self) -> bool {
+        let result: bool = msg_send![self.obj, is4K];
+        result
+    }
+
     /// Get the persistent UUID for this display
     pub unsafe fn uuid(&self) -> Option<String> {
         let uuid: *mut Object = msg_send![self.obj, uuid];
@@ -152,11 +378,204 @@ impl MPDisplay {
         }
     }
 
+    /// Get the mode macOS picks for this display by default
+    pub unsafe fn default_mode(&self) -> Option<MPDisplayMode> {
+        let mode: *mut Object = msg_send![self.obj, defaultMode];
+        if mode.is_null() {
+            None
+        } else {
+            Some(MPDisplayMode { obj: mode })
+        }
+    }
+
+    /// Get the mode matching the panel's native pixels
+    pub unsafe fn native_mode(&self) -> Option<MPDisplayMode> {
+        let mode: *mut Object = msg_send![self.obj, nativeMode];
+        if mode.is_null() {
+            None
+        } else {
+            Some(MPDisplayMode { obj: mode })
+        }
+    }
+
+    /// Check if the display is in its default mode
+    pub unsafe fn in_default_mode(&self) -> bool {
+        let result: bool = msg_send![self.obj, inDefaultMode];
+        result
+    }
+
     /// Set the display mode by mode number
     /// Returns the result code (0 = success)
     pub unsafe fn set_mode_number(&self, mode_number: i32) -> i32 {
         msg_send![self.obj, setModeNumber: mode_number]
     }
+
+    /// Get the current orientation in degrees (0, 90, 180 or 270)
+    pub unsafe fn orientation(&self) -> i32 {
+        msg_send![self.obj, orientation]
+    }
+
+    /// Set the orientation in degrees (0, 90, 180 or 270)
+    pub unsafe fn set_orientation(&self, degrees: i32) {
+        let _: () = msg_send![self.obj, setOrientation: degrees];
+    }
+
+    /// Check if the display's orientation can be changed
+    pub unsafe fn can_change_orientation(&self) -> bool {
+        let result: bool = msg_send![self.obj, canChangeOrientation];
+        result
+    }
+
+    /// Check if the display has a sensor that rotates it automatically
+    pub unsafe fn has_rotation_sensor(&self) -> bool {
+        let result: bool = msg_send![self.obj, hasRotationSensor];
+        result
+    }
+
+    /// Get the display's bounds in the global display space, in points
+    pub unsafe fn display_bounds(&self) -> CGRect {
+        msg_send![self.obj, displayBounds]
+    }
+
+    /// Get the display's bounds in hardware pixels
+    pub unsafe fn hardware_bounds(&self) -> CGRect {
+        msg_send![self.obj, hardwareBounds]
+    }
+
+    /// Check if the display supports underscan
+    pub unsafe fn supports_underscan(&self) -> bool {
+        let result: bool = msg_send![self.obj, supportsUnderscan];
+        result
+    }
+
+    /// Get the current underscan value
+    pub unsafe fn underscan(&self) -> i32 {
+        msg_send![self.obj, underscan]
+    }
+
+    /// Set the underscan value, within `min_underscan()..=max_underscan()`
+    pub unsafe fn set_underscan(&self, value: i32) {
+        let _: () = msg_send![self.obj, setUnderscan: value];
+    }
+
+    /// Get the smallest supported underscan value
+    pub unsafe fn min_underscan(&self) -> i32 {
+        msg_send![self.obj, minUnderscan]
+    }
+
+    /// Get the largest supported underscan value
+    pub unsafe fn max_underscan(&self) -> i32 {
+        msg_send![self.obj, maxUnderscan]
+    }
+
+    /// Check if the display supports overscan
+    pub unsafe fn supports_overscan(&self) -> bool {
+        let result: bool = msg_send![self.obj, supportsOverscan];
+        result
+    }
+
+    /// Check if overscan is enabled
+    pub unsafe fn overscan_enabled(&self) -> bool {
+        let result: bool = msg_send![self.obj, overscanEnabled];
+        result
+    }
+
+    /// Enable or disable overscan
+    pub unsafe fn set_overscan_enabled(&self, enabled: bool) {
+        let _: () = msg_send![self.obj, setOverscanEnabled: enabled];
+    }
+
+    /// Check if the display is part of a mirror set
+    pub unsafe fn is_mirrored(&self) -> bool {
+        let result: bool = msg_send![self.obj, isMirrored];
+        result
+    }
+
+    /// Check if the display is the master of its mirror set
+    pub unsafe fn is_mirror_master(&self) -> bool {
+        let result: bool = msg_send![self.obj, isMirrorMaster];
+        result
+    }
+
+    /// Get the ID of the master of the display's mirror set
+    pub unsafe fn mirror_master_display_id(&self) -> i32 {
+        msg_send![self.obj, mirrorMasterDisplayID]
+    }
+
+    /// Check if the display shows the menu bar
+    pub unsafe fn has_menu_bar(&self) -> bool {
+        let result: bool = msg_send![self.obj, hasMenuBar];
+        result
+    }
+
+    /// Whether the display offers HDR variants of its modes
+    pub unsafe fn has_hdr_modes(&self) -> bool {
+        let result: bool = msg_send![self.obj, hasHDRModes];
+        result
+    }
+
+    /// Whether HDR variants of modes are preferred, i.e. HDR is on
+    pub unsafe fn prefer_hdr_modes(&self) -> bool {
+        let result: bool = msg_send![self.obj, preferHDRModes];
+        result
+    }
+
+    /// Turn HDR on or off by preferring or avoiding HDR variants of modes
+    pub unsafe fn set_prefer_hdr_modes(&self, prefer: bool) {
+        let _: () = msg_send![self.obj, setPreferHDRModes: prefer];
+    }
+
+    /// Whether the display offers reference presets
+    pub unsafe fn has_presets(&self) -> bool {
+        let result: bool = msg_send![self.obj, hasPresets];
+        result
+    }
+
+    /// Reference presets offered by the display (Pro Display XDR, MacBook Pro)
+    pub unsafe fn presets(&self) -> Option<Vec<MPDisplayPreset>> {
+        let array: *mut Object = msg_send![self.obj, presets];
+        if array.is_null() {
+            return None;
+        }
+
+        let count: usize = msg_send![array, count];
+        let mut presets = Vec::with_capacity(count);
+
+        for i in 0..count {
+            let preset_obj: *mut Object = msg_send![array, objectAtIndex: i];
+            if !preset_obj.is_null() {
+                presets.push(MPDisplayPreset { obj: preset_obj });
+            }
+        }
+
+        Some(presets)
+    }
+
+    /// The preset the display is currently using
+    pub unsafe fn active_preset(&self) -> Option<MPDisplayPreset> {
+        let preset: *mut Object = msg_send![self.obj, activePreset];
+        if preset.is_null() {
+            None
+        } else {
+            Some(MPDisplayPreset { obj: preset })
+        }
+    }
+
+    /// The preset the display uses out of the box
+    pub unsafe fn default_preset(&self) -> Option<MPDisplayPreset> {
+        let preset: *mut Object = msg_send![self.obj, defaultPreset];
+        if preset.is_null() {
+            None
+        } else {
+            Some(MPDisplayPreset { obj: preset })
+        }
+    }
+
+    /// Activate a preset; returns whether MonitorPanel accepted it
+    pub unsafe fn set_active_preset(&self, preset: &MPDisplayPreset) -> bool {
+        let result: bool = msg_send![self.obj, setActivePreset: preset.obj];
+        result
+    }
 }
 
 impl MPDisplayMode {
@@ -214,6 +633,24 @@ impl MPDisplayMode {
         result
     }
 
+    /// Check if mode is the display's safe fallback mode
+    pub unsafe fn is_safe_mode(&self) -> bool {
+        let result: bool = msg_send![self.obj, isSafeMode];
+        result
+    }
+
+    /// Check if mode is interlaced
+    pub unsafe fn is_interlaced(&self) -> bool {
+        let result: bool = msg_send![self.obj, isInterlaced];
+        result
+    }
+
+    /// Check if mode stretches the picture to fill the display
+    pub unsafe fn is_stretched(&self) -> bool {
+        let result: bool = msg_send![self.obj, isStretched];
+        result
+    }
+
     /// Check if mode is user visible
     pub unsafe fn is_user_visible(&self) -> bool {
         let result: bool = msg_send![self.obj, isUserVisible];
@@ -225,6 +662,34 @@ impl MPDisplayMode {
         msg_send![self.obj, modeNumber]
     }
 
+    /// Get the precise refresh rate in Hz (e.g. 59.94), if reported
+    pub unsafe fn scan_rate(&self) -> Option<f64> {
+        let rate: *mut Object = msg_send![self.obj, scanRate];
+        if rate.is_null() {
+            return None;
+        }
+        let value: f64 = msg_send![rate, doubleValue];
+        Some(value)
+    }
+
+    /// Get the color depth from the underlying CGS mode description
+    pub unsafe fn depth(&self) -> Option<i32> {
+        let desc: *const CGSDisplayModeDescription = msg_send![self.obj, modeDescription];
+        if desc.is_null() {
+            return None;
+        }
+        unsafe { Some((*desc).depth) }
+    }
+
+    /// Get the bits per color channel from the underlying CGS mode description
+    pub unsafe fn bits_per_sample(&self) -> Option<i32> {
+        let desc: *const CGSDisplayModeDescription = msg_send![self.obj, modeDescription];
+        if desc.is_null() {
+            return None;
+        }
+        unsafe { Some((*desc).bits_per_sample) }
+    }
+
     /// Return the underlying Objective-C object pointer as a usize.
     pub unsafe fn object_ptr(&self) -> usize {
         self.obj as usize
@@ -245,3 +710,37 @@ impl MPDisplayMode {
         Some(c_str.to_string_lossy().into_owned())
     }
 }
+
+impl MPDisplayPreset {
+    /// Name shown in System Settings, such as "HDR Video (P3-ST 2084)"
+    pub unsafe fn name(&self) -> Option<String> {
+        unsafe { ns_string(msg_send![self.obj, presetName]) }
+    }
+
+    /// Longer description of what the preset is for
+    pub unsafe fn preset_description(&self) -> Option<String> {
+        unsafe { ns_string(msg_send![self.obj, presetDescription]) }
+    }
+
+    /// Group the preset is listed under in System Settings
+    pub unsafe fn group(&self) -> i64 {
+        msg_send![self.obj, presetGroup]
+    }
+
+    /// Whether the preset can be used with the display's current configuration
+    pub unsafe fn is_valid(&self) -> bool {
+        let result: bool = msg_send![self.obj, isValid];
+        result
+    }
+
+    /// Whether the preset is user-editable rather than a factory reference
+    pub unsafe fn is_writable(&self) -> bool {
+        let result: bool = msg_send![self.obj, isWritable];
+        result
+    }
+
+    /// Whether both wrap the same MonitorPanel preset
+    pub fn same_as(&self, other: &MPDisplayPreset) -> bool {
+        self.obj == other.obj
+    }
+}
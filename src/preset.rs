@@ -0,0 +1,90 @@
+// Reference presets
+// Pro Display XDR and recent MacBook Pro panels offer reference presets that fix the
+// colour space, white point and peak brightness. Presets are identified by name, which
+// is what profiles record.
+
+use crate::error::Error;
+use crate::monitor_panel::{MPDisplay, MPDisplayPreset};
+
+/// Presets offered by a display, or an error if it has none
+pub unsafe fn display_presets(mp_display: &MPDisplay) -> Result<Vec<MPDisplayPreset>, Error> {
+    unsafe {
+        let presets = if mp_display.has_presets() {
+            mp_display.presets().unwrap_or_default()
+        } else {
+            Vec::new()
+        };
+        if presets.is_empty() {
+            return Err(Error::Invalid(format!(
+                "Display ID {} does not offer reference presets",
+                mp_display.display_id()
+            )));
+        }
+        Ok(presets)
+    }
+}
+
+/// Find a preset by name, ignoring case
+pub unsafe fn find_preset(mp_display: &MPDisplay, name: &str) -> Result<MPDisplayPreset, Error> {
+    unsafe {
+        let presets = display_presets(mp_display)?;
+        let names: Vec<String> = presets.iter().filter_map(|preset| preset.name()).collect();
+        presets
+            .into_iter()
+            .find(|preset| {
+                preset
+                    .name()
+                    .is_some_and(|preset_name| preset_name.eq_ignore_ascii_case(name))
+            })
+            .ok_or_else(|| {
+                Error::NotFound(format!(
+                    "Display ID {} has no preset named '{}' (available: {})",
+                    mp_display.display_id(),
+                    name,
+                    names.join(", ")
+                ))
+            })
+    }
+}
+
+/// Name of the active preset, for displays that offer presets
+pub unsafe fn active_preset_name(mp_display: &MPDisplay) -> Option<String> {
+    unsafe {
+        if !mp_display.has_presets() {
+            return None;
+        }
+        mp_display.active_preset().and_then(|preset| preset.name())
+    }
+}
+
+/// Activate the preset named `name`, checking that it is valid and that it took effect
+pub unsafe fn activate_preset(mp_display: &MPDisplay, name: &str) -> Result<(), Error> {
+    unsafe {
+        let preset = find_preset(mp_display, name)?;
+        let preset_name = preset.name().unwrap_or_else(|| name.to_string());
+        if !preset.is_valid() {
+            return Err(Error::Invalid(format!(
+                "Preset '{}' cannot be used with the current configuration of display ID {}",
+                preset_name,
+                mp_display.display_id()
+            )));
+        }
+        if !mp_display.set_active_preset(&preset) {
+            return Err(Error::Platform(format!(
+                "Display ID {} rejected preset '{}'",
+                mp_display.display_id(),
+                preset_name
+            )));
+        }
+
+        let active = active_preset_name(mp_display);
+        if active.as_deref() != Some(preset_name.as_str()) {
+            return Err(Error::Platform(format!(
+                "Display ID {} did not switch to preset '{}'",
+                mp_display.display_id(),
+                preset_name
+            )));
+        }
+    }
+    Ok(())
+}
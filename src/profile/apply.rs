@@ -0,0 +1,120 @@
+use super::{Origin, Profile};
+use crate::arrangement::arrange_displays;
+use crate::brightness::CoreDisplay_Display_SetUserBrightness;
+use crate::error::Error;
+use crate::mode_safety::check_mode_change;
+use crate::mode_set::{RetryPolicy, set_mode};
+use crate::monitor_panel::MPDisplayMgr;
+use crate::preset::{activate_preset, active_preset_name};
+use crate::rotation::rotate_display;
+
+/// Apply a profile to the connected displays
+///
+/// Displays in the profile that are not connected are skipped with a warning. Presets,
+/// HDR, modes, rotation and brightness are applied per display; origins are applied together in a
+/// single display configuration transaction, snapped together so the arrangement stays
+/// valid if a display changed size.
+/// With `closest`, a saved mode without an exact match falls back to the nearest mode.
+/// Unsafe mode changes are refused unless `force` is set; transient mode change failures
+/// are retried per `retry`.
+pub fn apply_profile(
+    profile: &Profile,
+    closest: bool,
+    force: bool,
+    retry: RetryPolicy,
+) -> Result<(), Error> {
+    let mut origins: Vec<(u32, Origin)> = Vec::new();
+
+    unsafe {
+        let mgr = MPDisplayMgr::shared()
+            .or_else(|| MPDisplayMgr::new())
+            .ok_or_else(|| Error::Platform("MonitorPanel manager not available".to_string()))?;
+
+        for state in profile.displays.iter() {
+            let Some(mp_display) = mgr.display_with_uuid(&state.uuid) else {
+                eprintln!(
+                    "Warning: Display with UUID {} is not connected, skipping",
+                    state.uuid
+                );
+                continue;
+            };
+            let display_id = mp_display.display_id() as u32;
+
+            // A preset can change the refresh rate, so it goes before the mode
+            if let Some(preset) = &state.preset
+                && !active_preset_name(&mp_display)
+                    .is_some_and(|active| active.eq_ignore_ascii_case(preset))
+            {
+                println!("Setting display {} to preset '{}'", state.uuid, preset);
+                activate_preset(&mp_display, preset).map_err(|err| {
+                    Error::Invalid(format!("Display with UUID {}: {}", state.uuid, err))
+                })?;
+            }
+
+            // HDR decides which variant of a mode is used, so it also goes before the mode
+            if let Some(hdr) = state.hdr
+                && mp_display.has_hdr_modes()
+                && mp_display.prefer_hdr_modes() != hdr
+            {
+                println!(
+                    "Turning HDR {} on display {}",
+                    if hdr { "on" } else { "off" },
+                    state.uuid
+                );
+                mp_display.set_prefer_hdr_modes(hdr);
+            }
+
+            if let Some(mode) = &state.mode {
+                let mode_number = mode.resolve(&mp_display, closest).map_err(|err| {
+                    Error::NotFound(format!("Display with UUID {}: {}", state.uuid, err))
+                })?;
+
+                let current_mode_number = mp_display.current_mode().map(|m| m.mode_number());
+                if current_mode_number != Some(mode_number) {
+                    check_mode_change(&mp_display, mode_number, force).map_err(|err| {
+                        Error::Invalid(format!("Display with UUID {}: {}", state.uuid, err))
+                    })?;
+                    println!(
+                        "Setting display {} to mode #{} ({})",
+                        state.uuid, mode_number, mode
+                    );
+                    set_mode(&mp_display, mode_number, retry).map_err(|err| {
+                        Error::Platform(format!(
+                            "Failed to set display {} to mode #{}: {}",
+                            state.uuid, mode_number, err
+                        ))
+                    })?;
+                }
+            }
+
+            if let Some(rotation) = state.rotation
+                && mp_display.orientation() != rotation
+            {
+                println!("Rotating display {} to {}°", state.uuid, rotation);
+                rotate_display(&mp_display, rotation).map_err(|err| {
+                    Error::Invalid(format!("Display with UUID {}: {}", state.uuid, err))
+                })?;
+            }
+
+            if let Some(brightness) = state.brightness {
+                println!(
+                    "Setting display {} brightness to {}%",
+                    state.uuid, brightness
+                );
+                CoreDisplay_Display_SetUserBrightness(display_id, brightness as f64 / 100.0);
+            }
+
+            if let Some(origin) = state.origin {
+                origins.push((display_id, origin));
+            }
+        }
+    }
+
+    if !origins.is_empty() {
+        for m in arrange_displays(&origins)?.moves {
+            println!("Moving display ID {} to {}", m.display_id, m.to);
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,94 @@
+use core_graphics::display::CGDisplay;
+
+use super::{DisplayState, MonitorIdentity, Origin, Profile};
+use crate::brightness::user_brightness;
+use crate::error::Error;
+use crate::mode_descriptor::ModeDescriptor;
+use crate::monitor_panel::MPDisplayMgr;
+use crate::preset::active_preset_name;
+
+/// Capture the current configuration of all active displays as a profile
+pub fn capture_profile(name: &str) -> Result<Profile, Error> {
+    let active_displays = CGDisplay::active_displays()
+        .map_err(|err| Error::Platform(format!("Failed to get displays (error code: {})", err)))?;
+
+    let mut displays = Vec::new();
+
+    unsafe {
+        let mgr = MPDisplayMgr::shared()
+            .or_else(|| MPDisplayMgr::new())
+            .ok_or_else(|| Error::Platform("MonitorPanel manager not available".to_string()))?;
+        let mp_displays = mgr.displays().ok_or_else(|| {
+            Error::Platform("Could not get displays from MonitorPanel".to_string())
+        })?;
+
+        for mp_display in mp_displays.iter() {
+            let display_id = mp_display.display_id() as u32;
+            if !active_displays.contains(&display_id) {
+                continue;
+            }
+            // Displays without a persistent id cannot be matched again later
+            let Some(uuid) = mp_display.uuid() else {
+                continue;
+            };
+
+            // Brightness is only reported for displays that support it
+            let brightness =
+                user_brightness(display_id).map(|brightness| (brightness * 100.0).round() as u32);
+
+            let display = CGDisplay::new(display_id);
+            let bounds = display.bounds();
+            let name = mp_display.display_name();
+
+            displays.push(DisplayState {
+                uuid,
+                monitor: Some(MonitorIdentity::from_edid(
+                    display.vendor_number(),
+                    name.as_deref().unwrap_or_default(),
+                    display.serial_number(),
+                )),
+                name,
+                output: None,
+                brightness,
+                rotation: Some(mp_display.orientation()),
+                origin: Some(Origin {
+                    x: bounds.origin.x as i32,
+                    y: bounds.origin.y as i32,
+                }),
+                mode: mp_display
+                    .current_mode()
+                    .map(|mode| ModeDescriptor::from_mode(mp_display, &mode)),
+                preset: active_preset_name(mp_display),
+                hdr: mp_display
+                    .has_hdr_modes()
+                    .then(|| mp_display.prefer_hdr_modes()),
+            });
+        }
+    }
+
+    Ok(Profile {
+        name: name.to_string(),
+        displays,
+    })
+}
+
+/// Persistent screen ids of all active displays
+pub fn connected_display_uuids() -> Result<Vec<String>, Error> {
+    let active_displays = CGDisplay::active_displays()
+        .map_err(|err| Error::Platform(format!("Failed to get displays (error code: {})", err)))?;
+
+    unsafe {
+        let mgr = MPDisplayMgr::shared()
+            .or_else(|| MPDisplayMgr::new())
+            .ok_or_else(|| Error::Platform("MonitorPanel manager not available".to_string()))?;
+        let mp_displays = mgr.displays().ok_or_else(|| {
+            Error::Platform("Could not get displays from MonitorPanel".to_string())
+        })?;
+
+        Ok(mp_displays
+            .iter()
+            .filter(|mp_display| active_displays.contains(&(mp_display.display_id() as u32)))
+            .filter_map(|mp_display| mp_display.uuid())
+            .collect())
+    }
+}
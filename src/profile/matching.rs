@@ -0,0 +1,44 @@
+use super::{Profile, list_profiles, load_profile};
+use crate::error::Error;
+
+/// Identify a set of displays by their sorted persistent screen ids
+pub fn fingerprint(uuids: &[String]) -> String {
+    let mut uuids: Vec<String> = uuids.iter().map(|uuid| uuid.to_uppercase()).collect();
+    uuids.sort();
+    uuids.dedup();
+    uuids.join(",")
+}
+
+impl Profile {
+    /// Fingerprint of the displays this profile configures
+    pub fn fingerprint(&self) -> String {
+        let uuids: Vec<String> = self
+            .displays
+            .iter()
+            .map(|state| state.uuid.clone())
+            .collect();
+        fingerprint(&uuids)
+    }
+}
+
+/// Find the saved profile covering exactly the given set of displays
+///
+/// Profiles are checked in name order, so the alphabetically first match wins when
+/// several profiles were saved for the same set of displays. Profiles that fail to load
+/// are skipped with a warning.
+pub fn find_matching_profile(uuids: &[String]) -> Result<Option<Profile>, Error> {
+    let wanted = fingerprint(uuids);
+    for name in list_profiles()? {
+        let profile = match load_profile(&name) {
+            Ok(profile) => profile,
+            Err(err) => {
+                eprintln!("Warning: Skipping profile '{}': {}", name, err);
+                continue;
+            }
+        };
+        if profile.fingerprint() == wanted {
+            return Ok(Some(profile));
+        }
+    }
+    Ok(None)
+}
@@ -0,0 +1,83 @@
+// Named display profiles
+// A profile records the mode, preset, HDR, brightness, rotation and position of every display,
+// keyed by persistent screen id (UUID), so a desk setup can be restored later.
+
+mod apply;
+mod capture;
+mod matching;
+mod store;
+
+pub use apply::apply_profile;
+pub use capture::{capture_profile, connected_display_uuids};
+pub use matching::{find_matching_profile, fingerprint};
+pub use store::{delete_profile, list_profiles, load_profile, save_profile};
+
+use serde::{Deserialize, Serialize};
+
+pub use crate::layout::Origin;
+use crate::mode_descriptor::ModeDescriptor;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Profile {
+    pub name: String,
+    #[serde(default, rename = "display")]
+    pub displays: Vec<DisplayState>,
+}
+
+/// Saved state of a single display
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DisplayState {
+    /// Persistent screen id
+    pub uuid: String,
+    /// Display name at the time the profile was saved (informational only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Output name used by Linux tools (connector such as `DP-1` or a description)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+    /// EDID identity used to match the monitor in other tools' configuration
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub monitor: Option<MonitorIdentity>,
+    /// Brightness percentage (0-100)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub brightness: Option<u32>,
+    /// Rotation in degrees (0, 90, 180 or 270)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rotation: Option<i32>,
+    /// Top-left corner in the global display coordinate space
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub origin: Option<Origin>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mode: Option<ModeDescriptor>,
+    /// Name of the active reference preset, for displays that offer presets
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preset: Option<String>,
+    /// Whether HDR is on, for displays with HDR modes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hdr: Option<bool>,
+}
+
+/// EDID vendor, product and serial of a monitor, in the form GNOME records them
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct MonitorIdentity {
+    /// Three-letter PNP manufacturer id (e.g. `DEL`)
+    pub vendor: String,
+    /// Product name (e.g. `DELL U2720Q`)
+    pub product: String,
+    pub serial: String,
+}
+
+impl MonitorIdentity {
+    /// Build an identity from Core Graphics EDID numbers and the display name
+    ///
+    /// The serial uses GNOME's `0x%08x` form for the numeric EDID serial, which is what
+    /// GNOME records for monitors without a serial string descriptor.
+    pub fn from_edid(vendor_number: u32, product: &str, serial_number: u32) -> Self {
+        let letter = |shift: u32| (b'A' - 1 + ((vendor_number >> shift) & 0x1f) as u8) as char;
+        MonitorIdentity {
+            vendor: [letter(10), letter(5), letter(0)].iter().collect(),
+            product: product.to_string(),
+            serial: format!("0x{:08x}", serial_number),
+        }
+    }
+}
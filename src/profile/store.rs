@@ -0,0 +1,82 @@
+use std::fs;
+use std::path::PathBuf;
+
+use super::Profile;
+use crate::error::Error;
+
+/// Directory holding saved profiles:
+/// `$XDG_CONFIG_HOME/displayconfig/profiles`, or `~/.config/displayconfig/profiles`
+pub fn profiles_dir() -> Result<PathBuf, Error> {
+    let config_dir = match std::env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => {
+            let home = std::env::var_os("HOME")
+                .ok_or_else(|| Error::NotFound("HOME is not set".to_string()))?;
+            PathBuf::from(home).join(".config")
+        }
+    };
+    Ok(config_dir.join("displayconfig").join("profiles"))
+}
+
+fn profile_path(name: &str) -> Result<PathBuf, Error> {
+    if name.is_empty() || name.starts_with('.') || name.contains(['/', '\\']) {
+        return Err(Error::Invalid(format!("Invalid profile name '{}'", name)));
+    }
+    Ok(profiles_dir()?.join(format!("{}.toml", name)))
+}
+
+/// Write a profile to disk, replacing any existing profile with the same name
+pub fn save_profile(profile: &Profile) -> Result<PathBuf, Error> {
+    let path = profile_path(&profile.name)?;
+    let contents = toml::to_string_pretty(profile)
+        .map_err(|err| Error::Format(format!("Failed to serialize profile: {}", err)))?;
+
+    fs::create_dir_all(profiles_dir()?)?;
+    fs::write(&path, contents)?;
+    Ok(path)
+}
+
+/// Read a saved profile by name
+pub fn load_profile(name: &str) -> Result<Profile, Error> {
+    let path = profile_path(name)?;
+    if !path.exists() {
+        return Err(Error::NotFound(format!("Profile '{}' not found", name)));
+    }
+
+    let contents = fs::read_to_string(&path)?;
+    let mut profile: Profile = toml::from_str(&contents)
+        .map_err(|err| Error::Format(format!("Failed to parse {}: {}", path.display(), err)))?;
+    // The file name is authoritative if the file was renamed by hand
+    profile.name = name.to_string();
+    Ok(profile)
+}
+
+/// Names of all saved profiles, sorted
+pub fn list_profiles() -> Result<Vec<String>, Error> {
+    let dir = profiles_dir()?;
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut names = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "toml")
+            && let Some(stem) = path.file_stem()
+        {
+            names.push(stem.to_string_lossy().into_owned());
+        }
+    }
+    names.sort();
+    Ok(names)
+}
+
+/// Remove a saved profile
+pub fn delete_profile(name: &str) -> Result<(), Error> {
+    let path = profile_path(name)?;
+    if !path.exists() {
+        return Err(Error::NotFound(format!("Profile '{}' not found", name)));
+    }
+    fs::remove_file(path)?;
+    Ok(())
+}
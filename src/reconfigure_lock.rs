@@ -0,0 +1,165 @@
+// Reconfiguration lock
+// Login hooks, the `watch` loop and manual runs can reconfigure displays at the same
+// time and undo each other's changes. Every mutating operation holds an exclusive
+// advisory lock on a shared lock file for as long as it changes displays. It also holds
+// MonitorPanel's access lock, which only excludes other threads of the same process.
+//
+// The shared lock file lives in /var/run, where only root can create files, so other
+// users cannot plant it or a symlink in its place; it is never opened through a symlink.
+// Until a run as root has created it, each user locks a file in their own temporary
+// directory instead, so root and user sessions do not exclude each other yet. Waiting is
+// bounded, since any user who can read the file can hold the lock.
+
+use std::fs::{File, OpenOptions, TryLockError};
+use std::io::ErrorKind;
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::error::Error;
+use crate::monitor_panel::MPDisplayMgr;
+
+/// Lock file shared by every user, so that root login hooks and user sessions exclude
+/// each other; created by the first displayconfig run as root
+const LOCK_PATH: &str = "/var/run/displayconfig.lock";
+
+/// Name of the per-user lock file used until root has created the shared one
+const USER_LOCK_NAME: &str = "displayconfig.lock";
+
+/// Seconds to wait for the lock unless `--wait-timeout` says otherwise
+pub const DEFAULT_WAIT_TIMEOUT_SECS: u64 = 60;
+
+/// Longest `--wait-timeout` accepted, an hour
+pub const MAX_WAIT_TIMEOUT_SECS: u64 = 3600;
+
+/// How often to check whether the lock has been released while waiting
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// `O_NOFOLLOW` from <fcntl.h>
+const O_NOFOLLOW: i32 = 0x0100;
+
+/// Held while displays are being reconfigured; released when dropped or on exit
+pub struct ReconfigureLock {
+    // Closing the file releases the advisory lock
+    _file: File,
+    mgr: Option<MPDisplayMgr>,
+}
+
+fn busy(path: &Path, wait: Option<Duration>) -> Error {
+    Error::Busy(match wait {
+        None => format!(
+            "another displayconfig is reconfiguring the displays (lock held on {}); \
+             try again later or leave out --no-wait",
+            path.display()
+        ),
+        Some(timeout) => format!(
+            "another displayconfig is reconfiguring the displays (lock held on {} for over \
+             {}s); try again later or raise --wait-timeout",
+            path.display(),
+            timeout.as_secs()
+        ),
+    })
+}
+
+fn open_error(path: &Path, err: std::io::Error) -> Error {
+    Error::Platform(format!(
+        "Failed to open lock file {}: {}",
+        path.display(),
+        err
+    ))
+}
+
+/// Open a lock file without following a symlink, creating it readable by everyone
+fn open_no_follow(path: &Path, write: bool) -> std::io::Result<File> {
+    OpenOptions::new()
+        .read(true)
+        .write(write)
+        .create(write)
+        .truncate(false)
+        .mode(0o644)
+        .custom_flags(O_NOFOLLOW)
+        .open(path)
+}
+
+/// Open the shared lock file, read-only if root created it; users fall back to a lock
+/// file in their own temporary directory until it exists
+fn open_lock_file() -> Result<(File, PathBuf), Error> {
+    let shared = PathBuf::from(LOCK_PATH);
+    match open_no_follow(&shared, true) {
+        Ok(file) => return Ok((file, shared)),
+        Err(err) if err.kind() != ErrorKind::PermissionDenied => {
+            return Err(open_error(&shared, err));
+        }
+        Err(_) => {}
+    }
+    match open_no_follow(&shared, false) {
+        Ok(file) => return Ok((file, shared)),
+        Err(err) if err.kind() != ErrorKind::NotFound => return Err(open_error(&shared, err)),
+        Err(_) => {}
+    }
+
+    // macOS gives every user a private temporary directory
+    let own = std::env::temp_dir().join(USER_LOCK_NAME);
+    open_no_follow(&own, true)
+        .map(|file| (file, own.clone()))
+        .map_err(|err| open_error(&own, err))
+}
+
+/// Call `try_take` until it succeeds or the deadline passes; returns whether it succeeded
+fn poll_until(
+    deadline: Option<Instant>,
+    mut try_take: impl FnMut() -> Result<bool, Error>,
+) -> Result<bool, Error> {
+    let mut announced = false;
+    loop {
+        if try_take()? {
+            return Ok(true);
+        }
+        match deadline {
+            Some(deadline) if Instant::now() < deadline => {
+                if !announced {
+                    eprintln!("Waiting for another displayconfig to finish reconfiguring...");
+                    announced = true;
+                }
+                thread::sleep(POLL_INTERVAL);
+            }
+            _ => return Ok(false),
+        }
+    }
+}
+
+impl ReconfigureLock {
+    /// Take the lock, waiting up to `wait` for another process to release it, or failing
+    /// straight away when `wait` is `None`
+    pub fn acquire(wait: Option<Duration>) -> Result<Self, Error> {
+        let deadline = wait.map(|timeout| Instant::now() + timeout);
+        let (file, path) = open_lock_file()?;
+
+        let locked = poll_until(deadline, || match file.try_lock() {
+            Ok(()) => Ok(true),
+            Err(TryLockError::WouldBlock) => Ok(false),
+            Err(TryLockError::Error(err)) => Err(Error::Io(err)),
+        })?;
+        if !locked {
+            return Err(busy(&path, wait));
+        }
+
+        let mgr = unsafe { MPDisplayMgr::shared().or_else(|| MPDisplayMgr::new()) };
+        if let Some(mgr) = &mgr
+            && !poll_until(deadline, || Ok(unsafe { mgr.try_lock_access() }))?
+        {
+            return Err(busy(&path, wait));
+        }
+
+        Ok(ReconfigureLock { _file: file, mgr })
+    }
+}
+
+impl Drop for ReconfigureLock {
+    fn drop(&mut self) {
+        if let Some(mgr) = &self.mgr {
+            unsafe { mgr.unlock_access() };
+        }
+    }
+}
@@ -0,0 +1,326 @@
+// Display rescans
+// After docking, MonitorPanel can keep reporting displays and modes that are gone. A
+// rescan asks it to enumerate displays and modes again and reports what changed.
+
+use std::fmt;
+
+use crate::error::Error;
+use crate::mode_descriptor::{ModeDescriptor, ModeSnapshot, mode_snapshots};
+use crate::monitor_panel::MPDisplayMgr;
+
+/// A display as seen before or after a rescan
+#[derive(Clone, Debug)]
+pub struct ScannedDisplay {
+    pub id: u32,
+    pub uuid: Option<String>,
+    pub name: Option<String>,
+    pub current_mode: Option<ModeDescriptor>,
+    pub modes: Vec<ModeSnapshot>,
+}
+
+impl ScannedDisplay {
+    /// Whether both describe the same physical display
+    fn same_display(&self, other: &ScannedDisplay) -> bool {
+        match (&self.uuid, &other.uuid) {
+            (Some(a), Some(b)) => a.eq_ignore_ascii_case(b),
+            _ => self.id == other.id,
+        }
+    }
+
+    fn describe(&self) -> String {
+        match &self.name {
+            Some(name) => format!("display ID {} ({})", self.id, name),
+            None => format!("display ID {}", self.id),
+        }
+    }
+}
+
+/// A difference found by a rescan
+#[derive(Clone, Debug)]
+pub enum RescanChange {
+    DisplayAdded(String),
+    DisplayRemoved(String),
+    IdChanged {
+        display: String,
+        from: u32,
+    },
+    CurrentModeChanged {
+        display: String,
+        from: Option<ModeDescriptor>,
+        to: Option<ModeDescriptor>,
+    },
+    ModesAdded {
+        display: String,
+        modes: Vec<ModeDescriptor>,
+    },
+    ModesRemoved {
+        display: String,
+        modes: Vec<ModeDescriptor>,
+    },
+}
+
+impl fmt::Display for RescanChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mode_or_none = |mode: &Option<ModeDescriptor>| {
+            mode.as_ref()
+                .map_or_else(|| "none".to_string(), |mode| mode.to_string())
+        };
+        match self {
+            RescanChange::DisplayAdded(display) => write!(f, "+ {}", display),
+            RescanChange::DisplayRemoved(display) => write!(f, "- {}", display),
+            RescanChange::IdChanged { display, from } => {
+                write!(f, "~ {}: was display ID {}", display, from)
+            }
+            RescanChange::CurrentModeChanged { display, from, to } => write!(
+                f,
+                "~ {}: current mode {} -> {}",
+                display,
+                mode_or_none(from),
+                mode_or_none(to)
+            ),
+            RescanChange::ModesAdded { display, modes } => {
+                write!(f, "~ {}: {} mode(s) added", display, modes.len())?;
+                for mode in modes {
+                    write!(f, "\n    + {}", mode)?;
+                }
+                Ok(())
+            }
+            RescanChange::ModesRemoved { display, modes } => {
+                write!(f, "~ {}: {} mode(s) removed", display, modes.len())?;
+                for mode in modes {
+                    write!(f, "\n    - {}", mode)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Descriptors of the modes in `modes` that have no match in `other`
+fn missing_from(modes: &[ModeSnapshot], other: &[ModeSnapshot]) -> Vec<ModeDescriptor> {
+    modes
+        .iter()
+        .filter(|mode| {
+            !other
+                .iter()
+                .any(|candidate| candidate.descriptor.matches(&mode.descriptor))
+        })
+        .map(|mode| mode.descriptor.clone())
+        .collect()
+}
+
+/// Differences between two scans; displays are matched by UUID, modes by descriptor
+pub fn diff_scans(before: &[ScannedDisplay], after: &[ScannedDisplay]) -> Vec<RescanChange> {
+    let mut changes = Vec::new();
+
+    for old in before {
+        if !after.iter().any(|new| new.same_display(old)) {
+            changes.push(RescanChange::DisplayRemoved(old.describe()));
+        }
+    }
+
+    for new in after {
+        let Some(old) = before.iter().find(|old| old.same_display(new)) else {
+            changes.push(RescanChange::DisplayAdded(new.describe()));
+            continue;
+        };
+        let display = new.describe();
+
+        if old.id != new.id {
+            changes.push(RescanChange::IdChanged {
+                display: display.clone(),
+                from: old.id,
+            });
+        }
+        let same_mode = match (&old.current_mode, &new.current_mode) {
+            (Some(a), Some(b)) => a.matches(b),
+            (None, None) => true,
+            _ => false,
+        };
+        if !same_mode {
+            changes.push(RescanChange::CurrentModeChanged {
+                display: display.clone(),
+                from: old.current_mode.clone(),
+                to: new.current_mode.clone(),
+            });
+        }
+
+        let added = missing_from(&new.modes, &old.modes);
+        if !added.is_empty() {
+            changes.push(RescanChange::ModesAdded {
+                display: display.clone(),
+                modes: added,
+            });
+        }
+        let removed = missing_from(&old.modes, &new.modes);
+        if !removed.is_empty() {
+            changes.push(RescanChange::ModesRemoved {
+                display,
+                modes: removed,
+            });
+        }
+    }
+
+    changes
+}
+
+/// Displays and modes as MonitorPanel currently reports them
+unsafe fn scan(mgr: &MPDisplayMgr) -> Vec<ScannedDisplay> {
+    unsafe {
+        mgr.displays()
+            .unwrap_or_default()
+            .iter()
+            .map(|mp_display| ScannedDisplay {
+                id: mp_display.display_id() as u32,
+                uuid: mp_display.uuid(),
+                name: mp_display.display_name(),
+                current_mode: mp_display
+                    .current_mode()
+                    .map(|mode| ModeDescriptor::from_mode(mp_display, &mode)),
+                modes: mode_snapshots(mp_display),
+            })
+            .collect()
+    }
+}
+
+/// Make a manager enumerate the connected displays again
+///
+/// A manager keeps the display list it built when created, so a long-running process
+/// calls this to see displays connected or removed since.
+pub unsafe fn refresh_display_list(mgr: &MPDisplayMgr) {
+    unsafe {
+        mgr.refresh_displays();
+        mgr.update_displays_list();
+    }
+}
+
+/// Make MonitorPanel enumerate displays and modes again, returning what changed
+///
+/// The shared manager is refreshed, since that is the one every command looks displays
+/// up in; a separately allocated manager would be thrown away with the refreshed state.
+pub fn rescan_displays() -> Result<Vec<RescanChange>, Error> {
+    let mgr = unsafe { MPDisplayMgr::shared().or_else(|| MPDisplayMgr::new()) }
+        .ok_or_else(|| Error::Platform("MonitorPanel manager not available".to_string()))?;
+
+    unsafe {
+        let before = scan(&mgr);
+
+        refresh_display_list(&mgr);
+        for mp_display in mgr.displays().unwrap_or_default().iter() {
+            mp_display.refresh_modes();
+            mp_display.refresh_resolutions();
+        }
+
+        Ok(diff_scans(&before, &scan(&mgr)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mode(width: i32, height: i32, refresh_rate: f64) -> ModeDescriptor {
+        ModeDescriptor {
+            width,
+            height,
+            pixels_wide: width,
+            pixels_high: height,
+            scale: 1.0,
+            refresh_rate,
+            hidpi: false,
+            depth: None,
+            hdr: false,
+        }
+    }
+
+    fn snapshot(mode_number: i32, descriptor: ModeDescriptor) -> ModeSnapshot {
+        ModeSnapshot {
+            mode_number,
+            descriptor,
+            user_visible: true,
+            native: false,
+            interlaced: false,
+            stretched: false,
+        }
+    }
+
+    /// A monitor running 1920x1080@60 that also offers 1280x720@60
+    fn monitor(id: u32, uuid: &str) -> ScannedDisplay {
+        ScannedDisplay {
+            id,
+            uuid: Some(uuid.to_string()),
+            name: Some("DELL U2720Q".to_string()),
+            current_mode: Some(mode(1920, 1080, 60.0)),
+            modes: vec![
+                snapshot(1, mode(1920, 1080, 60.0)),
+                snapshot(2, mode(1280, 720, 60.0)),
+            ],
+        }
+    }
+
+    fn describe(changes: &[RescanChange]) -> Vec<String> {
+        changes.iter().map(|change| change.to_string()).collect()
+    }
+
+    #[test]
+    fn no_change() {
+        let scan = vec![monitor(2, "DELL-UUID")];
+        assert!(diff_scans(&scan, &scan.clone()).is_empty());
+    }
+
+    #[test]
+    fn display_added() {
+        let before = vec![monitor(1, "LAPTOP-UUID")];
+        let after = vec![monitor(1, "LAPTOP-UUID"), monitor(2, "DELL-UUID")];
+        assert_eq!(
+            describe(&diff_scans(&before, &after)),
+            vec!["+ display ID 2 (DELL U2720Q)"]
+        );
+    }
+
+    #[test]
+    fn display_removed() {
+        let before = vec![monitor(1, "LAPTOP-UUID"), monitor(2, "DELL-UUID")];
+        let after = vec![monitor(1, "LAPTOP-UUID")];
+        assert_eq!(
+            describe(&diff_scans(&before, &after)),
+            vec!["- display ID 2 (DELL U2720Q)"]
+        );
+    }
+
+    #[test]
+    fn current_mode_changed() {
+        let before = vec![monitor(2, "DELL-UUID")];
+        let mut after = before.clone();
+        after[0].current_mode = Some(mode(1280, 720, 60.0));
+        assert_eq!(
+            describe(&diff_scans(&before, &after)),
+            vec!["~ display ID 2 (DELL U2720Q): current mode 1920x1080 @ 60Hz -> 1280x720 @ 60Hz"]
+        );
+    }
+
+    #[test]
+    fn modes_added_and_removed() {
+        let before = vec![monitor(2, "DELL-UUID")];
+        let mut after = before.clone();
+        after[0].modes[1] = snapshot(2, mode(2560, 1440, 60.0));
+        let changes = diff_scans(&before, &after);
+        assert!(matches!(
+            changes.as_slice(),
+            [
+                RescanChange::ModesAdded { modes: added, .. },
+                RescanChange::ModesRemoved { modes: removed, .. },
+            ] if *added == vec![mode(2560, 1440, 60.0)] && *removed == vec![mode(1280, 720, 60.0)]
+        ));
+    }
+
+    #[test]
+    fn display_matched_by_uuid_across_id_change() {
+        let before = vec![monitor(2, "DELL-UUID")];
+        let after = vec![monitor(3, "dell-uuid")];
+        assert_eq!(
+            describe(&diff_scans(&before, &after)),
+            vec!["~ display ID 3 (DELL U2720Q): was display ID 2"]
+        );
+    }
+}
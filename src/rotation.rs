@@ -0,0 +1,53 @@
+// Display rotation
+// MonitorPanel reports and sets rotation as an orientation in degrees clockwise. Only
+// displays that report `canChangeOrientation` can be rotated.
+
+use crate::error::Error;
+use crate::monitor_panel::MPDisplay;
+
+/// Rotations supported by MonitorPanel, in degrees
+pub const ROTATIONS: [i32; 4] = [0, 90, 180, 270];
+
+/// Check that `degrees` is one of the supported rotations
+pub fn validate_rotation(degrees: i32) -> Result<(), Error> {
+    if ROTATIONS.contains(&degrees) {
+        Ok(())
+    } else {
+        Err(Error::Invalid(format!(
+            "Rotation must be 0, 90, 180 or 270 degrees, not {}",
+            degrees
+        )))
+    }
+}
+
+/// Check that a display can be rotated to `degrees`
+pub unsafe fn check_rotation(mp_display: &MPDisplay, degrees: i32) -> Result<(), Error> {
+    validate_rotation(degrees)?;
+    if unsafe { mp_display.can_change_orientation() } {
+        Ok(())
+    } else {
+        Err(Error::Invalid(format!(
+            "Display ID {} does not support rotation",
+            unsafe { mp_display.display_id() }
+        )))
+    }
+}
+
+/// Rotate a display, checking that it supports rotation and that the change took effect
+pub unsafe fn rotate_display(mp_display: &MPDisplay, degrees: i32) -> Result<(), Error> {
+    unsafe {
+        check_rotation(mp_display, degrees)?;
+        mp_display.set_orientation(degrees);
+
+        let orientation = mp_display.orientation();
+        if orientation != degrees {
+            return Err(Error::Platform(format!(
+                "Display ID {} stayed at {}° after rotating to {}°",
+                mp_display.display_id(),
+                orientation,
+                degrees
+            )));
+        }
+    }
+    Ok(())
+}
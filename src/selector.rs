@@ -0,0 +1,231 @@
+// Display selectors
+// Configuration files and commands refer to displays with selectors such as `main`,
+// `builtin`, a kind tag, a contextual id or a persistent UUID, resolved against the
+// connected displays.
+
+use std::fmt;
+use std::str::FromStr;
+
+use core_graphics::display::CGDisplay;
+
+use crate::display_kind::{DisplayTag, display_tags};
+use crate::display_name::connected_labels;
+use crate::error::Error;
+use crate::monitor_panel::{MPDisplay, MPDisplayMgr};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DisplaySelector {
+    /// The main display (`main`)
+    Main,
+    /// The built-in display (`builtin`)
+    BuiltIn,
+    /// Every display that is not built in (`external`)
+    External,
+    /// Contextual screen id (`id:2` or `2`)
+    Id(u32),
+    /// Persistent screen id (`uuid:…` or a bare UUID)
+    Uuid(String),
+    /// Display name, case-insensitive (`name:DELL U2720Q`, or `name:DELL U2720Q (2)` for
+    /// one of several identical monitors)
+    Name(String),
+    /// Every display with a kind tag (`tv`, `projector`, `tag:4k`)
+    Tag(DisplayTag),
+}
+
+/// Facts about a connected display used to evaluate selectors
+#[derive(Clone, Debug)]
+pub struct DisplayFacts {
+    pub id: u32,
+    pub uuid: Option<String>,
+    pub name: Option<String>,
+    /// Name with a numbered suffix when identical monitors are connected
+    pub label: Option<String>,
+    pub is_main: bool,
+    pub is_builtin: bool,
+    pub tags: Vec<DisplayTag>,
+}
+
+impl DisplaySelector {
+    pub fn matches(&self, display: &DisplayFacts) -> bool {
+        match self {
+            DisplaySelector::Main => display.is_main,
+            DisplaySelector::BuiltIn => display.is_builtin,
+            DisplaySelector::External => !display.is_builtin,
+            DisplaySelector::Id(id) => display.id == *id,
+            DisplaySelector::Uuid(uuid) => display
+                .uuid
+                .as_ref()
+                .is_some_and(|display_uuid| display_uuid.eq_ignore_ascii_case(uuid)),
+            DisplaySelector::Name(name) => display
+                .name
+                .iter()
+                .chain(display.label.iter())
+                .any(|display_name| display_name.eq_ignore_ascii_case(name)),
+            DisplaySelector::Tag(tag) => display.tags.contains(tag),
+        }
+    }
+
+    /// All displays in `displays` matched by this selector
+    pub fn select<'a>(&self, displays: &'a [DisplayFacts]) -> Vec<&'a DisplayFacts> {
+        displays
+            .iter()
+            .filter(|display| self.matches(display))
+            .collect()
+    }
+
+    /// The single display in `displays` matched by this selector
+    pub fn select_one<'a>(&self, displays: &'a [DisplayFacts]) -> Result<&'a DisplayFacts, Error> {
+        match self.select(displays).as_slice() {
+            [display] => Ok(display),
+            [] => Err(Error::NotFound(format!(
+                "No display matches selector '{}'",
+                self
+            ))),
+            matched => Err(Error::Invalid(format!(
+                "Selector '{}' matches {} displays; choose one with id:N or a UUID",
+                self,
+                matched.len()
+            ))),
+        }
+    }
+}
+
+impl FromStr for DisplaySelector {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let invalid = || Error::Invalid(format!("Invalid display selector '{}'", s));
+
+        if let Some((kind, value)) = s.split_once(':') {
+            let value = value.trim();
+            return match kind.trim().to_ascii_lowercase().as_str() {
+                "id" => value
+                    .parse()
+                    .map(DisplaySelector::Id)
+                    .map_err(|_| invalid()),
+                "uuid" if !value.is_empty() => Ok(DisplaySelector::Uuid(value.to_string())),
+                "name" if !value.is_empty() => Ok(DisplaySelector::Name(value.to_string())),
+                "tag" => DisplayTag::from_name(value)
+                    .map(DisplaySelector::Tag)
+                    .ok_or_else(invalid),
+                _ => Err(invalid()),
+            };
+        }
+
+        match s.to_ascii_lowercase().as_str() {
+            "main" => Ok(DisplaySelector::Main),
+            "builtin" | "built-in" => Ok(DisplaySelector::BuiltIn),
+            "external" => Ok(DisplaySelector::External),
+            _ if !s.is_empty() && s.chars().all(|c| c.is_ascii_digit()) => {
+                s.parse().map(DisplaySelector::Id).map_err(|_| invalid())
+            }
+            _ if is_uuid(s) => Ok(DisplaySelector::Uuid(s.to_string())),
+            _ => DisplayTag::from_name(s)
+                .map(DisplaySelector::Tag)
+                .ok_or_else(invalid),
+        }
+    }
+}
+
+impl fmt::Display for DisplaySelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DisplaySelector::Main => write!(f, "main"),
+            DisplaySelector::BuiltIn => write!(f, "builtin"),
+            DisplaySelector::External => write!(f, "external"),
+            DisplaySelector::Id(id) => write!(f, "id:{}", id),
+            DisplaySelector::Uuid(uuid) => write!(f, "uuid:{}", uuid),
+            DisplaySelector::Name(name) => write!(f, "name:{}", name),
+            DisplaySelector::Tag(tag) => write!(f, "tag:{}", tag),
+        }
+    }
+}
+
+/// Whether `s` looks like a UUID (8-4-4-4-12 hex digits)
+fn is_uuid(s: &str) -> bool {
+    let groups: Vec<&str> = s.split('-').collect();
+    groups.len() == 5
+        && groups
+            .iter()
+            .zip([8, 4, 4, 4, 12])
+            .all(|(group, len)| group.len() == len && group.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+/// Gather selector facts for all active displays
+pub fn connected_displays() -> Result<Vec<DisplayFacts>, Error> {
+    let active_displays = CGDisplay::active_displays()
+        .map_err(|err| Error::Platform(format!("Failed to get displays (error code: {})", err)))?;
+
+    let mgr = unsafe { MPDisplayMgr::shared().or_else(|| MPDisplayMgr::new()) }
+        .ok_or_else(|| Error::Platform("MonitorPanel manager not available".to_string()))?;
+
+    let labels = connected_labels();
+
+    Ok(active_displays
+        .iter()
+        .map(|display_id| {
+            let display = CGDisplay::new(*display_id);
+            let mp_display = unsafe { mgr.display_with_id(*display_id) };
+            DisplayFacts {
+                id: *display_id,
+                uuid: mp_display
+                    .as_ref()
+                    .and_then(|mp_display| unsafe { mp_display.uuid() }),
+                name: mp_display
+                    .as_ref()
+                    .and_then(|mp_display| unsafe { mp_display.display_name() }),
+                label: labels
+                    .iter()
+                    .find(|label| label.id == *display_id)
+                    .map(|label| label.label.clone()),
+                is_main: display.is_main(),
+                is_builtin: display.is_builtin(),
+                tags: mp_display
+                    .as_ref()
+                    .map(|mp_display| unsafe { display_tags(mp_display) })
+                    .unwrap_or_default(),
+            }
+        })
+        .collect())
+}
+
+/// Contextual ids of every connected display a selector matches, for commands that filter
+/// displays; matching none is an error
+pub fn select_displays(selector: &str) -> Result<Vec<u32>, Error> {
+    let displays = connected_displays()?;
+    let selector: DisplaySelector = selector.parse()?;
+    let matched: Vec<u32> = selector
+        .select(&displays)
+        .iter()
+        .map(|display| display.id)
+        .collect();
+    if matched.is_empty() {
+        return Err(Error::NotFound(format!(
+            "No display matches selector '{}'",
+            selector
+        )));
+    }
+    Ok(matched)
+}
+
+/// Resolve a selector to the single connected display it matches, with its MonitorPanel
+/// display
+pub fn resolve_display(selector: &str) -> Result<(u32, MPDisplay), Error> {
+    let displays = connected_displays()?;
+    let selector: DisplaySelector = selector.parse()?;
+    let display_id = selector.select_one(&displays)?.id;
+
+    let mp_display = unsafe {
+        MPDisplayMgr::shared()
+            .or_else(|| MPDisplayMgr::new())
+            .and_then(|mgr| mgr.display_with_id(display_id))
+    }
+    .ok_or_else(|| {
+        Error::NotFound(format!(
+            "Display ID {} not found in MonitorPanel",
+            display_id
+        ))
+    })?;
+    Ok((display_id, mp_display))
+}